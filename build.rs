@@ -1,27 +1,129 @@
+#[allow(dead_code)]
 #[path = "src/lib/pair.rs"]
 mod pair;
 
+#[allow(dead_code)]
 #[path = "src/lib/constants.rs"]
 mod constants;
 
+#[allow(dead_code)]
+#[path = "src/lib/width.rs"]
+mod width;
+
 use std::path::Path;
 
 use bk_tree::{metrics, BKTree};
 use constants::RAW_PAIRS;
-use pair::EmojiPair;
+use pair::{EmojiPair, Version};
+
+// The skin tones recorded for each emoji, in the same order as the
+// non-default variants of `pair::SkinTone`
+const SKIN_TONES: [emojis::SkinTone; 5] = [
+    emojis::SkinTone::Light,
+    emojis::SkinTone::MediumLight,
+    emojis::SkinTone::Medium,
+    emojis::SkinTone::MediumDark,
+    emojis::SkinTone::Dark,
+];
+
+fn skin_tones(emoji: &str) -> Vec<String> {
+    let Some(emoji) = emojis::get(emoji) else {
+        return vec![];
+    };
+
+    SKIN_TONES
+        .iter()
+        .map(|tone| emoji.with_skin_tone(*tone).map(|e| e.to_string()))
+        .collect::<Option<Vec<String>>>()
+        .unwrap_or_default()
+}
+
+fn category(group: emojis::Group) -> &'static str {
+    match group {
+        emojis::Group::SmileysAndEmotion => "Smileys & Emotion",
+        emojis::Group::PeopleAndBody => "People & Body",
+        emojis::Group::AnimalsAndNature => "Animals & Nature",
+        emojis::Group::FoodAndDrink => "Food & Drink",
+        emojis::Group::TravelAndPlaces => "Travel & Places",
+        emojis::Group::Activities => "Activities",
+        emojis::Group::Objects => "Objects",
+        emojis::Group::Symbols => "Symbols",
+        emojis::Group::Flags => "Flags",
+    }
+}
+
+// The other names of an emoji are the names it has in `RAW_PAIRS` and the
+// shortcodes GitHub and Slack know it by
+fn aliases(description: &str, emoji: &str) -> Vec<String> {
+    let shortcodes = emojis::get(emoji)
+        .into_iter()
+        .flat_map(|emoji| emoji.shortcodes());
+    let names = RAW_PAIRS
+        .iter()
+        .filter(|pair| pair.1 == emoji)
+        .map(|pair| pair.0);
+
+    let mut aliases: Vec<String> = Vec::new();
+    for alias in names.chain(shortcodes) {
+        if alias != description && !aliases.iter().any(|known| known == alias) {
+            aliases.push(alias.to_string());
+        }
+    }
+
+    aliases
+}
+
+fn keywords(emoji: &str) -> Vec<String> {
+    let Some(emoji) = emojis::get(emoji) else {
+        return vec![];
+    };
 
-pub fn fill_bk_tree() {
+    let mut keywords: Vec<String> = Vec::new();
+    for word in emoji
+        .name()
+        .split(|c: char| c.is_whitespace() || c == ':' || c == ',')
+        .filter(|word| !word.is_empty())
+    {
+        let word = word.to_lowercase();
+        if !keywords.contains(&word) {
+            keywords.push(word);
+        }
+    }
+
+    keywords
+}
+
+fn emoji_pairs() -> Vec<EmojiPair> {
+    RAW_PAIRS
+        .iter()
+        .map(|pair| EmojiPair {
+            description: pair.0.to_string(),
+            emoji: pair.1.to_string(),
+            skin_tones: skin_tones(pair.1),
+            aliases: aliases(pair.0, pair.1),
+            category: emojis::get(pair.1)
+                .map(|emoji| category(emoji.group()).to_string())
+                .unwrap_or_default(),
+            keywords: keywords(pair.1),
+            // The `emojis` crate calls the Emoji version the Unicode version
+            version: emojis::get(pair.1)
+                .map(|emoji| Version {
+                    major: emoji.unicode_version().major(),
+                    minor: emoji.unicode_version().minor(),
+                })
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+pub fn fill_bk_tree(pairs: &[EmojiPair]) {
     // step #1: initialize BK-tree
     let mut tree: BKTree<EmojiPair> = BKTree::new(metrics::Levenshtein);
 
     // step #2: insert each pair into BK-tree
-    for pair in RAW_PAIRS {
+    for pair in pairs {
         println!("{:?}", pair);
-        let suggestion = EmojiPair {
-            description: pair.0.to_string(),
-            emoji: pair.1.to_string(),
-        };
-        tree.add(suggestion);
+        tree.add(pair.clone());
     }
 
     // step #3: serialize tree into binary format using `bincode`
@@ -32,9 +134,21 @@ pub fn fill_bk_tree() {
     std::fs::write(path, encoded_tree).unwrap();
 }
 
+// The BK-tree can only be searched, so the pairs are also saved as a list
+// in their original order for anything that needs to go through all of them
+pub fn fill_emoji_list(pairs: &[EmojiPair]) {
+    let encoded_list: Vec<u8> = bincode::serialize(pairs).unwrap();
+
+    let path: &Path = Path::new("./public/static/emojilist.raw");
+    std::fs::write(path, encoded_list).unwrap();
+}
+
 fn main() {
-    // Only re-build and serialize the BKTree if codepairs.rs changes
-    println!("cargo:rerun-if-changed=src/constants.rs");
+    // Only re-build and serialize the BKTree if the emoji data changes
+    println!("cargo:rerun-if-changed=src/lib/constants.rs");
+    println!("cargo:rerun-if-changed=src/lib/pair.rs");
 
-    fill_bk_tree();
+    let pairs = emoji_pairs();
+    fill_bk_tree(&pairs);
+    fill_emoji_list(&pairs);
 }
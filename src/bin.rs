@@ -1,46 +1,37 @@
 mod cli;
-use emojicp;
 
 use crate::cli::entry;
-use bk_tree::BKTree;
 use clap::Parser;
-use emojicp::emoji::Emoji;
-use emojicp::pair::EmojiPair;
-use emojicp::types::{Args, Carousel, EmojiCarousel, EmojiError};
-use emojicp::ui::*;
-use std::env;
+use emojicp::constants::EXIT_CANCELED;
+use emojicp::types::Args;
 use std::error::Error;
+use std::process::ExitCode;
 
-fn main() -> Result<(), Box<dyn Error>> {
+fn main() -> ExitCode {
     // Parse the command-line input and run the program
     let args = Args::parse();
 
-    Ok(entry(&args)?)
-}
-
-/*
-fn main() {
-    // Default to return successful exit code
-    let mut exit_code: i32 = 0;
-
-    let args: Vec<String> = env::args().collect();
-
-    if let Some(search_term) = args.get(1) {
-        let key = EmojiPair {
-            description: search_term.to_owned(),
-            emoji: "".to_owned(), // doesn't matter for search
-        };
-
-        if let Some(result) = tree.find_exact(&key) {
-            clipboard::set(result.emoji.to_string());
-        } else {
-            exit_code = 1;
+    match entry(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            let code = error.exit_code();
+
+            // Canceling the picker is the user's choice, not a failure
+            // worth reporting
+            if code != EXIT_CANCELED {
+                // Report what caused the error as well, e.g.
+                // "cannot read configuration file `...`: No such file or directory"
+                let mut message = error.to_string();
+                let mut source = error.source();
+                while let Some(cause) = source {
+                    message.push_str(&format!(": {}", cause));
+                    source = cause.source();
+                }
+
+                eprintln!("emojicp: {}", message);
+            }
+
+            ExitCode::from(code)
         }
-    } else {
-        let mut viewer = EmojiCarousel::new(tree);
-        viewer.run();
     }
-
-    std::process::exit(exit_code);
 }
-*/
@@ -1,107 +1,139 @@
-use std::error::Error;
-use std::io::stdin;
+use std::io::{self, Write};
 
-use bk_tree::BKTree;
-use clipboard_anywhere::set_clipboard;
-use termion::event::Key;
-use termion::input::TermRead;
-use termion::raw::IntoRawMode;
+use clap::error::ErrorKind;
+use clap::CommandFactory;
 
-use emojicp::constants::*;
-use emojicp::emoji::Emoji;
+use emojicp::clipboard;
+use emojicp::completions;
+use emojicp::config::{self, Config, OutputMode};
+use emojicp::error::Error;
+use emojicp::manpage;
 use emojicp::pair::EmojiPair;
-use emojicp::search::{search_exact, search_interactive};
-use emojicp::{carousel, types::*};
-
-pub fn entry(args: &Args) -> Result<(), Box<dyn Error>> {
-    match search(args) {
-        Ok(pair) => Ok(set_clipboard(&pair.emoji)
-            .map_err(|_| Box::new(EmojiError::CannotCopyEmojiToClipboard { emoji: pair.emoji }))?),
-        Err(err) => Err(err),
-    }
-}
+use emojicp::search::{
+    emoji_list, search_interactive, search_query, search_regex, search_supported,
+};
+use emojicp::types::*;
 
-pub fn search(args: &Args) -> Result<EmojiPair, Box<dyn Error>> {
-    match args.description.clone() {
-        Some(description) => {
-            // search for emoji directly
-            search_exact(description)
+pub fn entry(args: &Args) -> Result<(), Error> {
+    match &args.command {
+        Some(Command::Search { query, regex }) => {
+            let config = Config::load(&args.overrides)?;
+            list(&query.join(" "), *regex, &config)
+        }
+        Some(Command::Config { action }) => configure(action, &args.overrides),
+        Some(Command::ShellInit { shell }) => {
+            print!("{}", shell.init_script());
+            Ok(())
+        }
+        Some(Command::Completions { shell }) => {
+            completions::generate(*shell, &emoji_list()?, &mut io::stdout())
+                .map_err(Error::CannotWriteOutput)
         }
+        Some(Command::Manpage) => {
+            manpage::render(&mut io::stdout()).map_err(Error::CannotWriteOutput)
+        }
+        Some(Command::Help { topic }) => help(topic, &args.overrides),
         None => {
-            // start in interactive mode
-            Ok(search_interactive()?)
+            let config = Config::load(&args.overrides)?;
+            let pairs = search(args, &config)?;
+            let emojis: Vec<&str> = pairs
+                .iter()
+                .map(|pair| pair.with_skin_tone(config.skin_tone))
+                .collect();
+
+            output(&emojis.join(&config.separator), &config)
         }
     }
 }
 
-/*
-fn start_carousel() {
-    let mut stdout = self.display.into_raw_mode()?;
-
-    for key in stdin.keys() {
-        match c.unwrap() {
-            Key::Ctrl('c') => {
-                self.clear_screen(&mut stdout);
-                break;
-            }
-            Key::Char('\n') => match self.mode {
-                UserMode::Search => {} // do nothing
-                UserMode::Select => {
-                    clipboard::set(self.get_current_selection().unwrap().emoji.to_string());
-                    print!("{}{}\r", termion::clear::All, termion::cursor::Goto(1, 1));
-                    let _ = stdout.flush();
-                    break;
-                }
-            },
-            Key::Backspace => {
-                if !self.search_term.is_empty() {
-                    self.mode = UserMode::Search;
-                    self.move_cursor_search();
-                    self.delete_last_char();
-                    self.search_term = Some(remove_last_char(&self.search_term));
-
-                    if self.search_term.is_empty() {
-                        self.clear_suggestions();
-                        self.suggestions.clear();
-                    } else {
-                        self.redraw();
-                    }
-                }
-            }
-            Key::Up => match self.mode {
-                UserMode::Search => {}
-                UserMode::Select => {
-                    if !self.suggestions.is_empty() {
-                        self.move_cursor_up();
-                    }
-                }
-            },
-            Key::Down => match self.mode {
-                UserMode::Search => {
-                    if !self.suggestions.is_empty() {
-                        self.mode = UserMode::Select;
-                        self.move_cursor_select();
-                    }
-                }
-                UserMode::Select => {
-                    if !self.suggestions.is_empty() {
-                        self.move_cursor_down();
-                    }
-                }
-            },
-            Key::Char(typed_char) => {
-                self.mode = UserMode::Search;
-                self.move_cursor_search();
-                print!("{}", typed_char);
-                self.search_term += &typed_char.to_string();
-
-                // Update tracking of cursor
-                self.cursor_pos.x += 1;
-
-                self.redraw();
-            }
-            _ => {} // do nothing for other keys
+pub fn search(args: &Args, config: &Config) -> Result<Vec<EmojiPair>, Error> {
+    if args.names.is_empty() {
+        // start in interactive mode
+        search_interactive(config)
+    } else {
+        // search for each emoji directly
+        args.names
+            .iter()
+            .map(|name| search_supported(name.clone(), config.max_version))
+            .collect()
+    }
+}
+
+// `list` prints every emoji matching `query` next to its name, for
+// `emojicp search`. With `regex`, `query` is a regular expression
+pub fn list(query: &str, regex: bool, config: &Config) -> Result<(), Error> {
+    let pairs = if regex {
+        search_regex(query, config.max_version)?
+    } else {
+        search_query(query, config.search.tolerance, config.max_version)?
+    };
+
+    let mut stdout = io::stdout().lock();
+    for pair in &pairs {
+        writeln!(
+            stdout,
+            "{} {}",
+            pair.with_skin_tone(config.skin_tone),
+            pair.description
+        )
+        .map_err(Error::CannotWriteOutput)?;
+    }
+
+    Ok(())
+}
+
+pub fn output(emoji: &str, config: &Config) -> Result<(), Error> {
+    if matches!(config.output, OutputMode::Clipboard | OutputMode::Both) {
+        clipboard::copy(emoji, &config.clipboard)?;
+    }
+
+    if matches!(config.output, OutputMode::Stdout | OutputMode::Both) {
+        println!("{}", emoji);
+    }
+
+    Ok(())
+}
+
+pub fn configure(action: &ConfigCommand, overrides: &ConfigOverrides) -> Result<(), Error> {
+    match action {
+        ConfigCommand::Show => print!("{}", Config::load(overrides)?.to_toml()),
+        ConfigCommand::Path => println!("{}", config::config_path(overrides)?.display()),
+        ConfigCommand::Init { force } => {
+            let path = config::config_path(overrides)?;
+            config::init(&path, *force)?;
+            println!("{}", path.display());
         }
     }
+
+    Ok(())
+}
+
+// `help` prints the key bindings for `help keys` and the help of the named
+// subcommand otherwise
+pub fn help(topic: &[String], overrides: &ConfigOverrides) -> Result<(), Error> {
+    if topic == ["keys"] {
+        let keymap = Config::load(overrides)?
+            .keymap()
+            .map_err(|source| Error::InvalidKeybindings { source })?;
+        print!("{}", keymap.describe());
+        return Ok(());
+    }
+
+    let mut command = Args::command();
+    command.build();
+
+    let mut current = &mut command;
+    for name in topic {
+        current = match current.find_subcommand_mut(name) {
+            Some(subcommand) => subcommand,
+            None => Args::command()
+                .error(
+                    ErrorKind::InvalidSubcommand,
+                    format!("no help available for `{}`", topic.join(" ")),
+                )
+                .exit(),
+        };
+    }
+
+    current.print_long_help().map_err(Error::CannotWriteOutput)
 }
-*/
@@ -1,463 +1,392 @@
-use crate::clipboard;
-use crate::pair::EmojiPair;
-use crate::types::*;
 use bk_tree::BKTree;
-use std::io::{stdin, stdout, Stdout, Write};
-use termion::event::Key;
-use termion::input::TermRead;
-use termion::raw::IntoRawMode;
-use termion::raw::RawTerminal;
-
-// State management plan:
-// #1: Two separate state objects, one for SearchState, one for SelectState
-// #2: They are normal structs, not singletons, because singletons suck
-// #3: ApplicationMode is still an enum with two values, either SearchMode
-// or SelectMode. SearchMode takes a reference to SearchState, SelectMode
-// takes a reference to SelectState (should the enum hold the state directly?)
-// #4: I'd like to decouple the logic for drawing to the terminal from the
-// carousel functionality itself. Is there a way I can provide the interface
-// that termion expects rather than writing to the terminal directly a. la. mocks?
-// #5: Rather printing directly to the terminal, I can instead pass in
-// std::io::Write and write to that!
-// #6: any time the underlying state of the carousel changes, the UI may update
-// #7: Generally, I think it's better to put infinite loops at the topmost level
-// of the app hierarchy, i.e. in main(). If I take the infinite loop of out
-// Carousel, I would turn Carousel into a state machine where the methods
-// in the interface would update the state machine. That means the translation
-// between keyboard inputs, i.e. stdin, and calling search() would have to
-// be handled by something else. That's a big match statement that should
-// be covered in a function at least. Should that not be in the interface?
-// I guess, someone has to handle keypresses. What is the carousel's job?
-//
-// 1. Allowing the user to search for items
-// 2. Displaying a list of items to the user
-// 3. Allowing the user to move between items
-// 4. Allowing the user to select an item
-//
-// Given the responsibilities, I would say that it is actually not the
-// responsibility of the carousel to decode keypresses. I would opt for moving
-// the match statement in run() into a function that is called by main
-
-/*
-impl Carousel for EmojiCarousel {
-    type Item = String;
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+use crate::pair::{EmojiPair, Version};
+#[cfg(feature = "regex")]
+use crate::query::find_regex;
+use crate::query::Query;
+
+// `Carousel` trait is a generic interface for allowing the user to search for
+// an item, presenting the user with a list of items, and allowing the user to
+// select an item from that list. It only holds the state of the picker; the
+// UI decides how the items are drawn and which keys drive it
+pub trait Carousel {
+    // The type of data that the user can search for and select from the
+    // `Carousel`
+    type Item;
+
+    // `items` returns the items currently presented to the user
+    fn items(&self) -> &[Self::Item];
+
+    // `position` returns the index of the item the `Carousel` is rotated to,
+    // or `None` if it is at the default location where no item is selected
+    fn position(&self) -> Option<usize>;
+
+    // `search` the `Carousel` for `query`. Calling `search` updates the list
+    // of items presented to the user with the items that most closely match
+    // `query`. Calling `search` also rotates the `Carousel` to the default
+    // location
+    fn search(&mut self, query: &str);
+
+    // `next` rotates the `Carousel` to the next item in the list presented to
+    // the user, wrapping around to the first item after the last one
+    fn next(&mut self);
+
+    // `previous` rotates the `Carousel` to the previous item in the list
+    // presented to the user, wrapping around to the last item before the
+    // first one
+    fn previous(&mut self);
+
+    // `unselect` rotates the `Carousel` back to the default location
+    fn unselect(&mut self);
+
+    // `select` returns the item currently selected in the list of items
+    // presented to the user
+    fn select(&self) -> Option<&Self::Item>;
+
+    // `quit` stops the `Carousel`. Nothing is selected afterwards and
+    // rotating or searching it does nothing
+    fn quit(&mut self);
+}
 
-    fn new(display: &impl std::io::Write) -> Self {
-        // step #1: get the raw bytes from the embedded file
-        let emoji_file = Emoji::get("static/emojitree.raw").unwrap();
-        let encoded_tree = emoji_file.data.as_ref();
+// `EmojiCarousel` suggests the emojis matching the search term, read as a
+// `Query`, closest matches first, or as a regular expression
+pub struct EmojiCarousel {
+    // The BKTree used for fuzzy searches
+    tree: BKTree<EmojiPair>,
 
-        // step #2: decode BKTree
-        let tree: BKTree<EmojiPair> = bincode::deserialize(encoded_tree).unwrap();
+    // Maximum Levenshtein distance between the search term and a suggestion
+    tolerance: u32,
 
-        return Self {
-            display,
-            tree,
-            mode: ApplicationMode::Search {
-                search_cursor_pos: Coordinates { x: 0, y: 0 },
-            },
-            search_term: None,
-            suggestions: vec![],
-        };
-    }
+    // How many suggestions are presented at most
+    result_count: usize,
 
-    fn start(&mut self) -> Result<(), DisplayError> {
-        let mut stdout = self.display.into_raw_mode()?;
-
-        for key in stdin.keys() {
-            match c.unwrap() {
-                Key::Ctrl('c') => {
-                    self.clear_screen(&mut stdout);
-                    break;
-                }
-                Key::Char('\n') => match self.mode {
-                    UserMode::Search => {} // do nothing
-                    UserMode::Select => {
-                        clipboard::set(self.get_current_selection().unwrap().emoji.to_string());
-                        print!("{}{}\r", termion::clear::All, termion::cursor::Goto(1, 1));
-                        let _ = stdout.flush();
-                        break;
-                    }
-                },
-                Key::Backspace => {
-                    if !self.search_term.is_empty() {
-                        self.mode = UserMode::Search;
-                        self.move_cursor_search();
-                        self.delete_last_char();
-                        self.search_term = Some(remove_last_char(&self.search_term));
-
-                        if self.search_term.is_empty() {
-                            self.clear_suggestions();
-                            self.suggestions.clear();
-                        } else {
-                            self.redraw();
-                        }
-                    }
-                }
-                Key::Up => match self.mode {
-                    UserMode::Search => {}
-                    UserMode::Select => {
-                        if !self.suggestions.is_empty() {
-                            self.move_cursor_up();
-                        }
-                    }
-                },
-                Key::Down => match self.mode {
-                    UserMode::Search => {
-                        if !self.suggestions.is_empty() {
-                            self.mode = UserMode::Select;
-                            self.move_cursor_select();
-                        }
-                    }
-                    UserMode::Select => {
-                        if !self.suggestions.is_empty() {
-                            self.move_cursor_down();
-                        }
-                    }
-                },
-                Key::Char(typed_char) => {
-                    self.mode = UserMode::Search;
-                    self.move_cursor_search();
-                    print!("{}", typed_char);
-                    self.search_term += &typed_char.to_string();
-
-                    // Update tracking of cursor
-                    self.cursor_pos.x += 1;
-
-                    self.redraw();
-                }
-                _ => {} // do nothing for other keys
-            }
-        }
+    // Emojis introduced after this Emoji version are never presented
+    max_version: Option<Version>,
 
-        Ok(())
-    }
+    // Whether search terms are regular expressions rather than queries
+    #[cfg(feature = "regex")]
+    regex: bool,
 
-    fn search(&mut self, item: &String) {
-        // step #1: clear current suggestions
-        self.suggestions.clear();
+    // List of suggested emojis and their names currently being shown to the
+    // user
+    suggestions: Vec<EmojiPair>,
 
-        // step #2: perform search on tree
-        let tolerance = 5;
-        let key = EmojiPair {
-            description: item.clone(),
-            emoji: "".to_owned(), // doesn't matter for search
-        };
+    // Index of the selected suggestion, if any
+    position: Option<usize>,
 
-        let search_results = self.tree.find(&key, tolerance);
+    // Whether `quit` has been called
+    quit: bool,
+}
 
-        let mut ordered_suggestions: Vec<(u32, EmojiPair)> = vec![];
-        for result in search_results {
-            let suggestion: EmojiPair = result.1.to_owned();
-            ordered_suggestions.push((result.0, suggestion));
+impl EmojiCarousel {
+    pub fn new(tree: BKTree<EmojiPair>, tolerance: u32, result_count: usize) -> Self {
+        EmojiCarousel {
+            tree,
+            tolerance,
+            result_count,
+            max_version: None,
+            #[cfg(feature = "regex")]
+            regex: false,
+            suggestions: Vec::new(),
+            position: None,
+            quit: false,
         }
+    }
 
-        ordered_suggestions.sort_by_key(|k| k.0);
-        let mut new_suggestions: Vec<EmojiPair> = vec![];
-        for suggestion in ordered_suggestions {
-            new_suggestions.push(suggestion.1);
+    // `show` presents the emojis named in `names`, in that order, instead of
+    // search results. Names that aren't in the tree are skipped
+    pub fn show<S: AsRef<str>>(&mut self, names: &[S]) {
+        if self.quit {
+            return;
         }
 
-        // step #3: filter out anything that doesn't almost match
-        self.suggestions = new_suggestions
+        self.suggestions = names
             .iter()
-            .enumerate()
-            .filter(|&(_, v)| v.description.starts_with(item.as_str()))
-            .map(|(_, e)| e.to_owned())
-            .collect();
-
-        // step #4: save the first 5 results
-        self.suggestions = self
-            .suggestions
-            .iter()
-            .enumerate()
-            .filter(|&(i, _)| i < 5)
-            .map(|(_, e)| e.to_owned())
+            .filter_map(|name| {
+                self.tree.find_exact(&EmojiPair {
+                    description: name.as_ref().to_owned(),
+                    ..Default::default()
+                })
+            })
+            .filter(|pair| pair.is_supported_by(self.max_version))
+            .cloned()
             .collect();
+        self.position = None;
     }
 
-    fn next(&mut self) {}
-
-    fn previous(&mut self) {}
-
-    fn select(&mut self) -> Option<&Self::Item> {
-        match self.mode {
-            ApplicationMode::Select {
-                current_selection,
-                select_cursor_pos,
-            } => return self,
-            ApplicationMode::Search { search_cursor_pos } => return None,
+    // `rotate_to` rotates the `Carousel` straight to the item at `index`, as
+    // long as there is one
+    pub fn rotate_to(&mut self, index: usize) {
+        if !self.quit && index < self.suggestions.len() {
+            self.position = Some(index);
         }
     }
 
-    fn reset(&mut self) {
-        self.mode = ApplicationMode::Search {
-            search_cursor_pos: Coordinates { x: 0, y: 0 },
-        };
-        self.search_term = None;
-        self.suggestions = None;
+    // `set_result_count` changes how many suggestions later searches present
+    pub fn set_result_count(&mut self, result_count: usize) {
+        self.result_count = result_count;
     }
-}
-
-impl EmojiCarousel {
-    pub fn new(tree: BKTree<EmojiPair>) -> Self {
-        let starting_pos = Coordinates { x: 1, y: 1 };
 
-        Self {
-            tree,
-            mode: UserMode::Search,
-            search_term: "".to_owned(),
-            current_selection: 0,
-            suggestions: vec![],
-            cursor_pos: starting_pos,
-        }
+    // `set_max_version` leaves emojis newer than `max_version` out of later
+    // searches
+    pub fn set_max_version(&mut self, max_version: Option<Version>) {
+        self.max_version = max_version;
     }
 
-    fn init(&mut self) {
-        print!("{}{}", termion::clear::All, termion::cursor::Goto(1, 1));
-        println!(
-            "{}{}{}\r",
-            termion::style::Bold,
-            SEARCH_PROMPT,
-            termion::style::Reset
-        );
+    // `set_regex` makes later searches match every name and alias against
+    // the search term as a regular expression, or read it as a `Query` again
+    #[cfg(feature = "regex")]
+    pub fn set_regex(&mut self, regex: bool) {
+        self.regex = regex;
+    }
 
-        self.cursor_pos.x = SEARCH_PROMPT.len();
-        self.cursor_pos.y = 1;
+    pub fn has_quit(&self) -> bool {
+        self.quit
+    }
 
-        for line in self.suggestions.iter() {
-            println!("{}\r", line);
+    // `find` returns the emojis matching `query`, read as a `Query`. A query
+    // that doesn't parse matches nothing; the picker shows why
+    fn find(&self, query: &str) -> Vec<&EmojiPair> {
+        match query.parse::<Query>() {
+            Ok(query) => query.find(&self.tree, self.tolerance),
+            Err(_) => Vec::new(),
         }
-
-        self.move_cursor(self.cursor_pos);
     }
+}
 
-    fn clear_screen(&self, stdout: &mut RawTerminal<Stdout>) {
-        print!("{}{}", termion::clear::All, termion::cursor::Goto(1, 1));
-        stdout.flush().expect("stdout flushes successfully");
+impl Carousel for EmojiCarousel {
+    type Item = EmojiPair;
+
+    fn items(&self) -> &[EmojiPair] {
+        &self.suggestions
     }
 
-    pub fn get_current_selection(&self) -> Option<&EmojiPair> {
-        self.suggestions.get(self.current_selection as usize)
+    fn position(&self) -> Option<usize> {
+        self.position
     }
 
-    pub fn run(&mut self) {
-        let mut stdout = stdout().into_raw_mode().unwrap();
-        let stdin = stdin();
-        self.init();
-        for c in stdin.keys() {
-            match c.unwrap() {
-                Key::Ctrl('c') => {
-                    self.clear_screen(&mut stdout);
-                    break;
-                }
-                Key::Char('\n') => match self.mode {
-                    UserMode::Search => {} // do nothing
-                    UserMode::Select => {
-                        clipboard::set(self.get_current_selection().unwrap().emoji.to_string());
-                        print!("{}{}\r", termion::clear::All, termion::cursor::Goto(1, 1));
-                        let _ = stdout.flush();
-                        break;
-                    }
-                },
-                Key::Backspace => {
-                    if !self.search_term.is_empty() {
-                        self.mode = UserMode::Search;
-                        self.move_cursor_search();
-                        self.delete_last_char();
-                        self.search_term = remove_last_char(&self.search_term);
-
-                        if self.search_term.is_empty() {
-                            self.clear_suggestions();
-                            self.suggestions.clear();
-                        } else {
-                            self.redraw();
-                        }
-                    }
-                }
-                Key::Up => match self.mode {
-                    UserMode::Search => {}
-                    UserMode::Select => {
-                        if !self.suggestions.is_empty() {
-                            self.move_cursor_up();
-                        }
-                    }
-                },
-                Key::Down => match self.mode {
-                    UserMode::Search => {
-                        if !self.suggestions.is_empty() {
-                            self.mode = UserMode::Select;
-                            self.move_cursor_select();
-                        }
-                    }
-                    UserMode::Select => {
-                        if !self.suggestions.is_empty() {
-                            self.move_cursor_down();
-                        }
-                    }
-                },
-                Key::Char(typed_char) => {
-                    self.mode = UserMode::Search;
-                    self.move_cursor_search();
-                    print!("{}", typed_char);
-                    self.search_term += &typed_char.to_string();
-
-                    // Update tracking of cursor
-                    self.cursor_pos.x += 1;
-
-                    self.redraw();
-                }
-                _ => {} // do nothing for other keys
-            }
+    fn search(&mut self, query: &str) {
+        if self.quit {
+            return;
         }
-    }
 
-    fn delete_last_char(&mut self) {
-        // Move cursor back one
-        if !self.search_term.is_empty() {
-            let back_one = Coordinates {
-                x: self.cursor_pos.x - 1,
-                y: self.cursor_pos.y,
-            };
-            self.move_cursor(back_one);
+        #[cfg(feature = "regex")]
+        let results = if self.regex {
+            match Regex::new(query) {
+                Ok(regex) => find_regex(&regex, &self.tree),
+                Err(_) => Vec::new(),
+            }
+        } else {
+            self.find(query)
+        };
+        #[cfg(not(feature = "regex"))]
+        let results = self.find(query);
+
+        self.suggestions = results
+            .into_iter()
+            .filter(|pair| pair.is_supported_by(self.max_version))
+            .take(self.result_count)
+            .cloned()
+            .collect();
+        self.position = None;
+    }
 
-            // Print space to clear that character
-            print!(" ");
+    fn next(&mut self) {
+        if self.quit || self.suggestions.is_empty() {
+            return;
+        }
 
-            // Move cursor back one again
-            self.move_cursor(back_one);
+        self.position = match self.position {
+            Some(i) if i + 1 < self.suggestions.len() => Some(i + 1),
+            _ => Some(0),
+        };
+    }
 
-            // Update member variable
-            self.cursor_pos.x = back_one.x;
+    fn previous(&mut self) {
+        if self.quit || self.suggestions.is_empty() {
+            return;
         }
-    }
 
-    fn move_cursor(&mut self, new_pos: Coordinates) {
-        print!("{}", termion::cursor::Goto(new_pos.x(), new_pos.y()));
-        let _ = stdout().flush();
-        self.cursor_pos = new_pos;
+        self.position = match self.position {
+            Some(i) if i > 0 => Some(i - 1),
+            _ => Some(self.suggestions.len() - 1),
+        };
     }
 
-    fn move_cursor_search(&mut self) {
-        self.move_cursor(Coordinates {
-            x: SEARCH_PROMPT.len() + self.search_term.len(),
-            y: 1,
-        });
+    fn unselect(&mut self) {
+        self.position = None;
     }
 
-    fn move_cursor_select(&mut self) {
-        self.move_cursor(Coordinates { x: 1, y: 2 });
+    fn select(&self) -> Option<&EmojiPair> {
+        self.suggestions.get(self.position?)
     }
 
-    fn move_cursor_up(&mut self) {
-        if self.cursor_pos.y > 2 {
-            self.cursor_pos.y -= 1;
-            self.current_selection -= 1;
-        }
-
-        self.move_cursor(self.cursor_pos);
+    fn quit(&mut self) {
+        self.quit = true;
+        self.position = None;
     }
+}
 
-    fn move_cursor_down(&mut self) {
-        if self.cursor_pos.y < self.suggestions.len() + 1 {
-            self.cursor_pos.y += 1;
-            self.current_selection += 1;
+#[cfg(test)]
+mod tests {
+    use bk_tree::{metrics, BKTree};
+
+    use crate::carousel::{Carousel, EmojiCarousel};
+    use crate::pair::EmojiPair;
+
+    fn carousel() -> EmojiCarousel {
+        let mut tree = BKTree::new(metrics::Levenshtein);
+        for (description, emoji, version) in [
+            ("crab", "🦀", "1.0"),
+            ("crayon", "🖍️", "0.7"),
+            ("cricket", "🦗", "5.0"),
+            ("snake", "🐍", "0.6"),
+        ] {
+            tree.add(EmojiPair {
+                description: String::from(description),
+                emoji: String::from(emoji),
+                version: version.parse().unwrap(),
+                ..Default::default()
+            });
         }
 
-        self.move_cursor(self.cursor_pos);
+        EmojiCarousel::new(tree, 10, 10)
     }
 
-    fn redraw(&mut self) {
-        self.update_suggestions();
-        self.draw_suggestions();
-        self.move_cursor_search();
+    fn names(carousel: &EmojiCarousel) -> Vec<&str> {
+        carousel
+            .items()
+            .iter()
+            .map(|pair| pair.description.as_str())
+            .collect()
     }
 
-    fn clear_suggestions(&mut self) {
-        let old_pos = self.cursor_pos;
-        self.move_cursor_select();
-
-        for _ in &self.suggestions {
-            println!("{}\r", termion::clear::CurrentLine);
-        }
-        self.move_cursor(old_pos);
+    #[test]
+    fn test_search() {
+        let mut carousel = carousel();
+
+        // only names with a word starting with the query are suggested,
+        // closest first
+        carousel.search("cra");
+        assert_eq!(vec!["crab", "crayon"], names(&carousel));
+        assert!(carousel.select().is_none());
+
+        // searching rotates the carousel back to the default location
+        carousel.next();
+        carousel.search("s");
+        assert_eq!(vec!["snake"], names(&carousel));
+        assert_eq!(None, carousel.position());
+
+        carousel.search("ferris");
+        assert!(carousel.items().is_empty());
+
+        // the query can filter on more than the name
+        carousel.search("c v:>=1");
+        assert_eq!(vec!["crab", "cricket"], names(&carousel));
+
+        // and suggests nothing until it parses
+        carousel.search("c v:");
+        assert!(carousel.items().is_empty());
     }
 
-    fn update_suggestions(&mut self) {
-        // step #0: clear current suggestions
-        self.suggestions.clear();
-
-        // step #1: perform search on tree
-        let tolerance = 5;
-        let key = EmojiPair {
-            description: self.search_term.clone(),
-            emoji: "".to_owned(), // doesn't matter for search
-        };
+    #[test]
+    #[cfg(feature = "regex")]
+    fn test_regex() {
+        let mut carousel = carousel();
+        carousel.set_regex(true);
+
+        // every name is matched, in alphabetical order
+        carousel.search("[ae]");
+        assert_eq!(vec!["crab", "crayon", "cricket", "snake"], names(&carousel));
+        carousel.search("^cr.*n$");
+        assert_eq!(vec!["crayon"], names(&carousel));
+
+        // a pattern that doesn't compile suggests nothing
+        carousel.search("cr(");
+        assert!(carousel.items().is_empty());
+
+        carousel.set_regex(false);
+        carousel.search("cra");
+        assert_eq!(vec!["crab", "crayon"], names(&carousel));
+    }
 
-        let search_results = self.tree.find(&key, tolerance);
+    #[test]
+    fn test_result_count() {
+        let mut carousel = carousel();
+        carousel.set_result_count(1);
 
-        let mut ordered_suggestions: Vec<(u32, EmojiPair)> = vec![];
-        for result in search_results {
-            let suggestion: EmojiPair = result.1.to_owned();
-            ordered_suggestions.push((result.0, suggestion));
-        }
+        carousel.search("c");
+        assert_eq!(1, carousel.items().len());
+    }
 
-        ordered_suggestions.sort_by_key(|k| k.0);
-        let mut new_suggestions: Vec<EmojiPair> = vec![];
-        for suggestion in ordered_suggestions {
-            new_suggestions.push(suggestion.1);
-        }
+    #[test]
+    fn test_max_version() {
+        let mut carousel = carousel();
+        carousel.set_max_version(Some("1.0".parse().unwrap()));
 
-        // step #2.5: filter out anything that doesn't almost match
-        self.suggestions = new_suggestions
-            .iter()
-            .enumerate()
-            .filter(|&(_, v)| v.description.starts_with(self.search_term.as_str()))
-            .map(|(_, e)| e.to_owned())
-            .collect();
+        carousel.search("cr");
+        assert_eq!(vec!["crab", "crayon"], names(&carousel));
 
-        // step #3: save the first 5 results
-        self.suggestions = self
-            .suggestions
-            .iter()
-            .enumerate()
-            .filter(|&(i, _)| i < 5)
-            .map(|(_, e)| e.to_owned())
-            .collect();
+        carousel.show(&["cricket", "snake"]);
+        assert_eq!(vec!["snake"], names(&carousel));
     }
 
-    fn draw_suggestions(&mut self) {
-        // step #1: move cursor to the top of the select area
-        self.move_cursor_select();
-
-        // step #2: clear each suggestion and draw new suggestion
-        for suggestion in &self.suggestions {
-            print!("{}\r", termion::clear::CurrentLine);
-            println!("{}", suggestion);
-        }
+    #[test]
+    fn test_rotate() {
+        let mut carousel = carousel();
+
+        // rotating an empty carousel selects nothing
+        carousel.next();
+        carousel.previous();
+        assert_eq!(None, carousel.position());
+
+        carousel.search("cr");
+        carousel.next();
+        assert_eq!("crab", carousel.select().unwrap().description);
+        carousel.next();
+        carousel.next();
+        assert_eq!("cricket", carousel.select().unwrap().description);
+
+        // rotating past either end wraps around
+        carousel.next();
+        assert_eq!(Some(0), carousel.position());
+        carousel.previous();
+        assert_eq!(Some(2), carousel.position());
+
+        carousel.unselect();
+        carousel.previous();
+        assert_eq!(Some(2), carousel.position());
+
+        // rotating straight to an item only works if it exists
+        carousel.rotate_to(1);
+        assert_eq!("crayon", carousel.select().unwrap().description);
+        carousel.rotate_to(3);
+        assert_eq!(Some(1), carousel.position());
     }
-}
-
-fn remove_last_char(search_term: &str) -> String {
-    let mut chars = search_term.chars();
-    chars.next_back(); // pop last char
-    chars.as_str().to_string()
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::carousel::remove_last_char;
 
     #[test]
-    fn test_remove_last_char() {
-        // empty string returns an empty string
-        assert_eq!("", remove_last_char(""));
+    fn test_show() {
+        let mut carousel = carousel();
 
-        // single character string returns empty string
-        assert_eq!("", remove_last_char("&"));
+        carousel.show(&["snake", "ferris", "crab"]);
+        assert_eq!(vec!["snake", "crab"], names(&carousel));
+    }
 
-        // only last character is removed from a string of characters
-        assert_eq!("abc", remove_last_char("abcd"));
+    #[test]
+    fn test_quit() {
+        let mut carousel = carousel();
+        carousel.search("cr");
+        carousel.next();
+
+        carousel.quit();
+        assert!(carousel.has_quit());
+        assert!(carousel.select().is_none());
+
+        carousel.next();
+        carousel.search("snake");
+        assert!(carousel.select().is_none());
+        assert_eq!(3, carousel.items().len());
     }
 }
-*/
@@ -1,7 +1,91 @@
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use base64::Engine;
 use clipboard_anywhere::set_clipboard;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+// The mechanism used to copy the selected emoji to the clipboard
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardBackend {
+    // Let `clipboard-anywhere` pick between the native clipboard, WSL and
+    // OSC 52 depending on the environment
+    #[default]
+    Auto,
+
+    // Always write an OSC 52 escape sequence to the terminal
+    Osc52,
+
+    // Pipe the emoji into the command configured in `clipboard.command`
+    Command,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClipboardConfig {
+    // The mechanism used to copy the emoji to the clipboard
+    pub backend: ClipboardBackend,
+
+    // Program and arguments the emoji is piped into when `backend` is
+    // `command`, e.g. `["wl-copy"]`
+    pub command: Vec<String>,
+}
+
+// `copy` puts `selection` on the clipboard using the configured backend
+pub fn copy(selection: &str, config: &ClipboardConfig) -> Result<(), Error> {
+    let copied = match config.backend {
+        ClipboardBackend::Auto => set_clipboard(selection).map_err(Into::into),
+        ClipboardBackend::Osc52 => copy_osc_52(selection).map_err(Into::into),
+        ClipboardBackend::Command => copy_command(selection, &config.command).map_err(Into::into),
+    };
+
+    copied.map_err(|source| Error::CannotCopyEmojiToClipboard {
+        emoji: selection.to_owned(),
+        source,
+    })
+}
+
+// Write an OSC 52 escape sequence to the controlling terminal, so that the
+// emoji still reaches the clipboard if stdout has been redirected
+fn copy_osc_52(selection: &str) -> io::Result<()> {
+    let sequence = format!(
+        "\x1B]52;c;{}\x07",
+        base64::engine::general_purpose::STANDARD.encode(selection)
+    );
+
+    match OpenOptions::new().write(true).open("/dev/tty") {
+        Ok(mut tty) => tty.write_all(sequence.as_bytes()),
+        Err(_) => io::stdout().write_all(sequence.as_bytes()),
+    }
+}
+
+fn copy_command(selection: &str, command: &[String]) -> io::Result<()> {
+    let Some((program, args)) = command.split_first() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "no clipboard command configured",
+        ));
+    };
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    // Dropping stdin closes the pipe so the command sees end-of-file
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(selection.as_bytes())?;
+    }
 
-pub fn set(selection: String) {
-    // If we fail to set to the clipboard, ignore
-    // the error and exit the program gracefully
-    _ = set_clipboard(selection.as_str());
+    if child.wait()?.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other("clipboard command exited unsuccessfully"))
+    }
 }
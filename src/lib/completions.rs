@@ -0,0 +1,150 @@
+use std::io::{self, Write};
+
+use clap::builder::{PossibleValue, PossibleValuesParser};
+use clap::{Command, CommandFactory};
+use clap_complete::Shell;
+
+use crate::pair::EmojiPair;
+use crate::types::Args;
+
+// `generate` writes a completion script for `shell` to `out`. The emoji
+// names are completed from `pairs`, with the glyph as the description in
+// shells that can show one
+pub fn generate(shell: Shell, pairs: &[EmojiPair], out: &mut dyn Write) -> io::Result<()> {
+    let mut command = command(pairs);
+    let name = command.get_name().to_owned();
+
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut command, name, &mut script);
+    let script = String::from_utf8_lossy(&script);
+
+    // clap only completes positional arguments in bash and zsh, so the names
+    // are added to the scripts for the other shells by hand
+    let script = match shell {
+        Shell::Fish => format!("{}{}", script, fish_names(&command, pairs)),
+        Shell::Elvish => insert_names(&script, "&'emojicp'= {\n", pairs, |pair| {
+            format!(
+                "            cand {} {}\n",
+                quote_doubled(&pair.description),
+                quote_doubled(&pair.emoji)
+            )
+        }),
+        Shell::PowerShell => insert_names(&script, "'emojicp' {\n", pairs, |pair| {
+            format!(
+                "            [CompletionResult]::new({0}, {0}, [CompletionResultType]::ParameterValue, {1})\n",
+                quote_doubled(&pair.description),
+                quote_doubled(&pair.emoji)
+            )
+        }),
+        _ => script.into_owned(),
+    };
+
+    out.write_all(script.as_bytes())
+}
+
+// `command` is the command-line interface with the emoji names as the
+// possible values of the `NAME` argument. Only the completion scripts use
+// it; unknown names are still reported by the search itself
+fn command(pairs: &[EmojiPair]) -> Command {
+    let names: Vec<PossibleValue> = pairs
+        .iter()
+        .map(|pair| PossibleValue::new(pair.description.clone()).help(pair.emoji.clone()))
+        .collect();
+
+    Args::command().mut_arg("names", |arg| {
+        arg.value_parser(PossibleValuesParser::new(names))
+    })
+}
+
+// `fish_names` completes the names for as long as no subcommand was given
+fn fish_names(command: &Command, pairs: &[EmojiPair]) -> String {
+    let subcommands: Vec<&str> = command
+        .get_subcommands()
+        .map(|subcommand| subcommand.get_name())
+        .collect();
+
+    pairs
+        .iter()
+        .map(|pair| {
+            format!(
+                "complete -c {} -n \"not __fish_seen_subcommand_from {}\" -f -a {} -d {}\n",
+                command.get_name(),
+                subcommands.join(" "),
+                quote_escaped(&pair.description),
+                quote_escaped(&pair.emoji)
+            )
+        })
+        .collect()
+}
+
+// `insert_names` adds a line for every pair right after `marker`, which
+// opens the completions of the top-level command
+fn insert_names(
+    script: &str,
+    marker: &str,
+    pairs: &[EmojiPair],
+    line: impl Fn(&EmojiPair) -> String,
+) -> String {
+    let Some(index) = script.find(marker) else {
+        return script.to_owned();
+    };
+
+    let (head, tail) = script.split_at(index + marker.len());
+    let names: String = pairs.iter().map(line).collect();
+
+    format!("{}{}{}", head, names, tail)
+}
+
+// Elvish and PowerShell escape a single quote inside single quotes by
+// doubling it
+fn quote_doubled(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+// fish escapes single quotes and backslashes inside single quotes with a
+// backslash
+fn quote_escaped(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use clap_complete::Shell;
+
+    use crate::completions::generate;
+    use crate::pair::EmojiPair;
+
+    fn script(shell: Shell) -> String {
+        let pairs = [
+            EmojiPair {
+                description: String::from("crab"),
+                emoji: String::from("🦀"),
+                ..Default::default()
+            },
+            EmojiPair {
+                description: String::from("snake"),
+                emoji: String::from("🐍"),
+                ..Default::default()
+            },
+        ];
+
+        let mut script = Vec::new();
+        generate(shell, &pairs, &mut script).unwrap();
+        String::from_utf8(script).unwrap()
+    }
+
+    #[test]
+    fn test_generate() {
+        let bash = script(Shell::Bash);
+        assert!(bash.contains("crab snake"));
+        assert!(bash.contains("shell-init"));
+
+        // the glyph is shown next to the name where the shell supports it
+        assert!(script(Shell::Zsh).contains("crab\\:\"🦀\""));
+        assert!(script(Shell::Fish).contains("-f -a 'crab' -d '🦀'"));
+        assert!(script(Shell::Elvish).contains("cand 'crab' '🦀'"));
+        assert!(script(Shell::PowerShell).contains(
+            "[CompletionResult]::new('snake', 'snake', [CompletionResultType]::ParameterValue, '🐍')"
+        ));
+    }
+}
@@ -0,0 +1,847 @@
+#[cfg(feature = "cli")]
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::de::value::{Error as ValueError, StrDeserializer};
+use serde::de::IntoDeserializer;
+use serde::{Deserialize, Serialize};
+
+use crate::clipboard::ClipboardConfig;
+use crate::constants::*;
+use crate::error::Error;
+use crate::keymap::{KeyMap, KeyMapError, KeybindingsConfig};
+use crate::pair::{SkinTone, Version};
+use crate::theme::{ColorMode, Theme, ThemeConfig, ThemeError};
+#[cfg(feature = "cli")]
+use crate::types::ConfigOverrides;
+
+// `Config` holds every setting the user can change. Settings are resolved in
+// increasing order of precedence: the built-in defaults, the configuration
+// file, the `EMOJICP_*` environment variables and finally command-line flags
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    // Where the selected emoji is sent
+    pub output: OutputMode,
+
+    // Skin tone used for emojis that support one
+    pub skin_tone: SkinTone,
+
+    // Text placed between emojis when several are copied at once
+    pub separator: String,
+
+    // Whether the interactive picker stays open after copying, adding each
+    // selection to what has been copied so far
+    pub keep_open: bool,
+
+    // Number of rows the picker takes up beneath the prompt. Without it the
+    // picker takes over the whole terminal
+    pub height: Option<u16>,
+
+    // How the interactive picker lays out the suggestions when it starts
+    pub view: View,
+
+    // Terminal columns an emoji's name takes up in the list of suggestions,
+    // before the emoji itself
+    pub name_width: usize,
+
+    // Whether the picker checks that the terminal draws emoji sequences
+    // such as 👩‍🦰 as a single emoji, showing their code points if it doesn't
+    pub render_check: bool,
+
+    // The newest Emoji version the emojis may come from, for sending them
+    // to systems that show newer emojis as boxes
+    pub max_version: Option<Version>,
+
+    // Whether the interactive picker is drawn in color
+    pub color: ColorMode,
+
+    // Title of the search box in interactive mode
+    pub prompt: String,
+
+    // How often the interactive UI wakes up when no input arrives
+    pub tick_rate_ms: u64,
+
+    pub clipboard: ClipboardConfig,
+
+    pub search: SearchConfig,
+
+    pub keybindings: KeybindingsConfig,
+
+    pub theme: ThemeConfig,
+}
+
+// Where the selected emoji is sent once the user has picked one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum OutputMode {
+    // Copy the emoji to the clipboard
+    #[default]
+    Clipboard,
+
+    // Print the emoji to stdout
+    Stdout,
+
+    // Copy the emoji to the clipboard and print it to stdout
+    Both,
+}
+
+// How the suggestions are laid out in the interactive picker
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum View {
+    // One suggestion per line, with its name
+    #[default]
+    List,
+
+    // Rows of glyphs grouped by category, like a phone's emoji keyboard
+    Grid,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SearchConfig {
+    // Maximum Levenshtein distance between the search term and a suggestion
+    pub tolerance: u32,
+
+    // How many suggestions are shown in interactive mode
+    pub result_count: usize,
+}
+
+// Every key of the configuration file with a short description, as listed in
+// the manual page
+pub const CONFIG_KEYS: &[(&str, &str)] = &[
+    ("output", "Where the selected emoji is sent: clipboard, stdout or both"),
+    (
+        "skin_tone",
+        "Skin tone for emojis that support one: default, light, medium_light, medium, medium_dark or dark",
+    ),
+    ("separator", "Text placed between emojis when several are copied at once"),
+    ("keep_open", "Keep the interactive picker open after copying"),
+    (
+        "height",
+        "Draw the picker in this many rows beneath the prompt instead of the whole terminal",
+    ),
+    (
+        "view",
+        "How the picker lays out the suggestions when it starts: list or grid",
+    ),
+    (
+        "name_width",
+        "Columns an emoji's name takes up in the list of suggestions; longer names are cut short",
+    ),
+    (
+        "render_check",
+        "Check that the terminal draws emoji sequences as one emoji, showing code points if not",
+    ),
+    (
+        "max_version",
+        "Leave out emojis introduced after this Emoji version, e.g. \"12.0\"",
+    ),
+    (
+        "color",
+        "Draw the picker in color: auto, always or never. auto honors NO_COLOR",
+    ),
+    ("prompt", "Title of the search box in interactive mode"),
+    (
+        "tick_rate_ms",
+        "How often the interactive UI wakes up when no input arrives",
+    ),
+    (
+        "clipboard.backend",
+        "How the emoji is copied to the clipboard: auto, osc52 or command",
+    ),
+    (
+        "clipboard.command",
+        "Program and arguments the emoji is piped into when the backend is command",
+    ),
+    (
+        "search.tolerance",
+        "Maximum edit distance between the search term and a suggestion",
+    ),
+    (
+        "search.result_count",
+        "How many suggestions are shown in interactive mode",
+    ),
+    (
+        "keybindings.preset",
+        "Built-in key bindings to start from: default, vim or emacs",
+    ),
+    ("keybindings.global", "Key bindings that apply everywhere"),
+    (
+        "keybindings.input",
+        "Key bindings that apply while typing in the search box",
+    ),
+    (
+        "keybindings.list",
+        "Key bindings that apply while a suggestion is highlighted",
+    ),
+    (
+        "keybindings.grid",
+        "Key bindings that apply while an emoji in the grid is highlighted",
+    ),
+    (
+        "theme.name",
+        "Built-in theme to start from: default, high_contrast, monochrome or solarized",
+    ),
+    (
+        "theme.input",
+        "Style of the search box while typing in it, e.g. \"bold yellow on blue\"",
+    ),
+    (
+        "theme.list",
+        "Style of the suggestions while one of them is highlighted",
+    ),
+    ("theme.highlight", "Style of the highlighted suggestion"),
+    ("theme.borders", "Style of the borders around each part of the picker"),
+    (
+        "theme.status",
+        "Style of the hints and messages beneath the suggestions",
+    ),
+    (
+        "theme.error",
+        "Style of the message explaining what is wrong with the search term",
+    ),
+];
+
+// Every environment variable `emojicp` reads, with a short description
+pub const ENVIRONMENT: &[(&str, &str)] = &[
+    (ENV_CONFIG, "Path of the configuration file"),
+    (ENV_OUTPUT, "Overrides output"),
+    (ENV_CLIPBOARD_BACKEND, "Overrides clipboard.backend"),
+    (
+        ENV_CLIPBOARD_COMMAND,
+        "Overrides clipboard.command, split on whitespace",
+    ),
+    (ENV_SKIN_TONE, "Overrides skin_tone"),
+    (ENV_SEPARATOR, "Overrides separator"),
+    (ENV_KEEP_OPEN, "Overrides keep_open"),
+    (ENV_HEIGHT, "Overrides height"),
+    (ENV_VIEW, "Overrides view"),
+    (ENV_NAME_WIDTH, "Overrides name_width"),
+    (ENV_RENDER_CHECK, "Overrides render_check"),
+    (ENV_MAX_VERSION, "Overrides max_version"),
+    (ENV_COLOR, "Overrides color"),
+    (ENV_THEME, "Overrides theme.name"),
+    (
+        ENV_NO_COLOR,
+        "Turns colors off when set to anything, unless color is always",
+    ),
+    (ENV_PROMPT, "Overrides prompt"),
+    (ENV_TICK_RATE_MS, "Overrides tick_rate_ms"),
+    (ENV_TOLERANCE, "Overrides search.tolerance"),
+    (ENV_RESULT_COUNT, "Overrides search.result_count"),
+    (ENV_KEYMAP, "Overrides keybindings.preset"),
+    (
+        "XDG_CONFIG_HOME",
+        "Directory holding emojicp/config.toml, ~/.config by default",
+    ),
+    (
+        "XDG_DATA_HOME",
+        "Directory holding the emojicp favorites and history, ~/.local/share by default",
+    ),
+];
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            output: OutputMode::default(),
+            skin_tone: SkinTone::default(),
+            separator: String::new(),
+            keep_open: false,
+            height: None,
+            view: View::default(),
+            name_width: NAME_WIDTH,
+            render_check: true,
+            max_version: None,
+            color: ColorMode::default(),
+            prompt: String::from(SEARCH_PROMPT),
+            tick_rate_ms: DEFAULT_TICK_RATE_MS,
+            clipboard: ClipboardConfig::default(),
+            search: SearchConfig::default(),
+            keybindings: KeybindingsConfig::default(),
+            theme: ThemeConfig::default(),
+        }
+    }
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        SearchConfig {
+            tolerance: DEFAULT_TOLERANCE,
+            result_count: DEFAULT_RESULT_COUNT,
+        }
+    }
+}
+
+impl Config {
+    // `load` builds the effective configuration from the configuration file,
+    // the environment and the command-line flags in `overrides`. A missing
+    // file at the default location is not an error, but a file requested
+    // explicitly with `--config` or `$EMOJICP_CONFIG` has to exist
+    #[cfg(feature = "cli")]
+    pub fn load(overrides: &ConfigOverrides) -> Result<Config, Error> {
+        let lookup = |name: &str| env::var(name).ok();
+
+        let explicit_path = overrides
+            .config
+            .clone()
+            .or_else(|| lookup(ENV_CONFIG).map(PathBuf::from));
+
+        let mut config = match explicit_path {
+            Some(path) => Config::from_file(&path)?,
+            None => match default_config_path(lookup) {
+                Some(path) if path.is_file() => Config::from_file(&path)?,
+                _ => Config::default(),
+            },
+        };
+
+        config.apply_env(lookup)?;
+        config.apply_overrides(overrides);
+
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Config, Error> {
+        let contents = fs::read_to_string(path).map_err(|source| Error::CannotReadConfig {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let config = Config::from_toml(&contents).map_err(|source| Error::InvalidConfig {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        // Catch unknown keys and actions while the file is being loaded
+        // rather than once the interactive picker starts
+        config
+            .keymap()
+            .map_err(|source| Error::InvalidConfigKeybindings {
+                path: path.to_path_buf(),
+                source,
+            })?;
+
+        Ok(config)
+    }
+
+    // `from_toml` reads the settings in `contents`. Key bindings are only
+    // checked by `keymap`
+    pub fn from_toml(contents: &str) -> Result<Config, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    pub fn keymap(&self) -> Result<KeyMap, KeyMapError> {
+        KeyMap::new(&self.keybindings)
+    }
+
+    // `theme` builds the styles of the picker, dropping the colors if
+    // `color` is `auto` and `NO_COLOR` is set
+    pub fn theme(&self) -> Result<Theme, ThemeError> {
+        let no_color = std::env::var_os(ENV_NO_COLOR).is_some_and(|value| !value.is_empty());
+        Theme::new(&self.theme, self.color.enabled(no_color))
+    }
+
+    pub fn to_toml(&self) -> String {
+        // Serializing plain data structures to TOML cannot fail
+        toml::to_string_pretty(self).expect("configuration is serializable")
+    }
+
+    // `apply_env` overrides settings with the `EMOJICP_*` environment
+    // variables, looking each one up with `lookup`
+    pub fn apply_env(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Result<(), Error> {
+        if let Some(value) = lookup(ENV_OUTPUT) {
+            self.output = parse_env_enum(ENV_OUTPUT, value)?;
+        }
+
+        if let Some(value) = lookup(ENV_CLIPBOARD_BACKEND) {
+            self.clipboard.backend = parse_env_enum(ENV_CLIPBOARD_BACKEND, value)?;
+        }
+
+        if let Some(value) = lookup(ENV_CLIPBOARD_COMMAND) {
+            self.clipboard.command = value.split_whitespace().map(String::from).collect();
+        }
+
+        if let Some(value) = lookup(ENV_SKIN_TONE) {
+            self.skin_tone = parse_env_enum(ENV_SKIN_TONE, value)?;
+        }
+
+        if let Some(value) = lookup(ENV_SEPARATOR) {
+            self.separator = value;
+        }
+
+        if let Some(value) = lookup(ENV_KEEP_OPEN) {
+            self.keep_open = parse_env(ENV_KEEP_OPEN, value)?;
+        }
+
+        if let Some(value) = lookup(ENV_HEIGHT) {
+            self.height = Some(parse_env(ENV_HEIGHT, value)?);
+        }
+
+        if let Some(value) = lookup(ENV_VIEW) {
+            self.view = parse_env_enum(ENV_VIEW, value)?;
+        }
+
+        if let Some(value) = lookup(ENV_NAME_WIDTH) {
+            self.name_width = parse_env(ENV_NAME_WIDTH, value)?;
+        }
+
+        if let Some(value) = lookup(ENV_RENDER_CHECK) {
+            self.render_check = parse_env(ENV_RENDER_CHECK, value)?;
+        }
+
+        if let Some(value) = lookup(ENV_MAX_VERSION) {
+            self.max_version = Some(parse_env(ENV_MAX_VERSION, value)?);
+        }
+
+        if let Some(value) = lookup(ENV_PROMPT) {
+            self.prompt = value;
+        }
+
+        if let Some(value) = lookup(ENV_TICK_RATE_MS) {
+            self.tick_rate_ms = parse_env(ENV_TICK_RATE_MS, value)?;
+        }
+
+        if let Some(value) = lookup(ENV_TOLERANCE) {
+            self.search.tolerance = parse_env(ENV_TOLERANCE, value)?;
+        }
+
+        if let Some(value) = lookup(ENV_RESULT_COUNT) {
+            self.search.result_count = parse_env(ENV_RESULT_COUNT, value)?;
+        }
+
+        if let Some(value) = lookup(ENV_KEYMAP) {
+            self.keybindings.preset = parse_env_enum(ENV_KEYMAP, value)?;
+        }
+
+        if let Some(value) = lookup(ENV_COLOR) {
+            self.color = parse_env_enum(ENV_COLOR, value)?;
+        }
+
+        if let Some(value) = lookup(ENV_THEME) {
+            self.theme.name = parse_env_enum(ENV_THEME, value)?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "cli")]
+    pub fn apply_overrides(&mut self, overrides: &ConfigOverrides) {
+        if let Some(output) = overrides.output {
+            self.output = output;
+        }
+
+        if let Some(backend) = overrides.clipboard_backend {
+            self.clipboard.backend = backend;
+        }
+
+        if let Some(skin_tone) = overrides.skin_tone {
+            self.skin_tone = skin_tone;
+        }
+
+        if let Some(separator) = &overrides.separator {
+            self.separator = separator.clone();
+        }
+
+        if let Some(keep_open) = overrides.keep_open() {
+            self.keep_open = keep_open;
+        }
+
+        if let Some(height) = overrides.height {
+            self.height = Some(height);
+        }
+
+        if let Some(view) = overrides.view {
+            self.view = view;
+        }
+
+        if let Some(name_width) = overrides.name_width {
+            self.name_width = name_width;
+        }
+
+        if let Some(render_check) = overrides.render_check() {
+            self.render_check = render_check;
+        }
+
+        if let Some(max_version) = overrides.max_version {
+            self.max_version = Some(max_version);
+        }
+
+        if let Some(result_count) = overrides.results {
+            self.search.result_count = result_count;
+        }
+
+        if let Some(tolerance) = overrides.tolerance {
+            self.search.tolerance = tolerance;
+        }
+
+        if let Some(preset) = overrides.keymap {
+            self.keybindings.preset = preset;
+        }
+
+        if let Some(color) = overrides.color {
+            self.color = color;
+        }
+
+        if let Some(theme) = overrides.theme {
+            self.theme.name = theme;
+        }
+    }
+}
+
+// `config_path` returns the configuration file that `Config::load` reads,
+// whether or not it exists yet
+#[cfg(feature = "cli")]
+pub fn config_path(overrides: &ConfigOverrides) -> Result<PathBuf, Error> {
+    let lookup = |name: &str| env::var(name).ok();
+
+    overrides
+        .config
+        .clone()
+        .or_else(|| lookup(ENV_CONFIG).map(PathBuf::from))
+        .or_else(|| default_config_path(lookup))
+        .ok_or(Error::NoConfigDirectory)
+}
+
+// `default_config_path` follows the XDG Base Directory specification:
+// `$XDG_CONFIG_HOME` is used if it is set to an absolute path, otherwise the
+// configuration lives under `$HOME/.config`
+pub fn default_config_path(lookup: impl Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    let config_home = lookup("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| {
+            lookup("HOME")
+                .filter(|home| !home.is_empty())
+                .map(|home| PathBuf::from(home).join(".config"))
+        })?;
+
+    Some(config_home.join(CONFIG_DIR_NAME).join(CONFIG_FILE_NAME))
+}
+
+// `default_data_dir` is where emojicp keeps state such as favorites:
+// `$XDG_DATA_HOME/emojicp`, falling back to `$HOME/.local/share/emojicp`
+pub fn default_data_dir(lookup: impl Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    let data_home = lookup("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| {
+            lookup("HOME")
+                .filter(|home| !home.is_empty())
+                .map(|home| PathBuf::from(home).join(".local").join("share"))
+        })?;
+
+    Some(data_home.join(CONFIG_DIR_NAME))
+}
+
+// `init` writes the default configuration to `path`, creating any missing
+// parent directories along the way
+pub fn init(path: &Path, force: bool) -> Result<(), Error> {
+    if path.exists() && !force {
+        return Err(Error::ConfigAlreadyExists {
+            path: path.to_path_buf(),
+        });
+    }
+
+    let cannot_write = |source| Error::CannotWriteConfig {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(cannot_write)?;
+    }
+
+    fs::write(path, Config::default().to_toml()).map_err(cannot_write)
+}
+
+fn parse_env<T: FromStr>(name: &str, value: String) -> Result<T, Error> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| Error::InvalidEnvironmentVariable {
+            name: name.to_owned(),
+            value,
+        })
+}
+
+fn parse_env_enum<'de, T: Deserialize<'de>>(name: &str, value: String) -> Result<T, Error> {
+    // Accept the snake_case spelling used in the configuration file as well
+    // as the kebab-case spelling used on the command line
+    let spelling = value.trim().to_lowercase().replace('-', "_");
+    let deserializer: StrDeserializer<ValueError> = spelling.as_str().into_deserializer();
+
+    T::deserialize(deserializer).map_err(|_| Error::InvalidEnvironmentVariable {
+        name: name.to_owned(),
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[cfg(feature = "cli")]
+    use clap::Parser;
+
+    use crate::clipboard::ClipboardBackend;
+    use crate::config::{
+        default_config_path, default_data_dir, Config, OutputMode, View, CONFIG_KEYS,
+    };
+    use crate::error::Error;
+    use crate::keymap::{KeyMapError, Preset};
+    use crate::pair::{SkinTone, Version};
+    use crate::theme::{ColorMode, ThemeConfig, ThemeName};
+    #[cfg(feature = "cli")]
+    use crate::types::{Args, ConfigOverrides};
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_config_from_toml() {
+        // an empty file yields the defaults
+        assert_eq!(Config::default(), Config::from_toml("").unwrap());
+
+        // settings that are present replace the defaults
+        let config = Config::from_toml(
+            r#"
+            output = "both"
+            skin_tone = "medium_dark"
+
+            [clipboard]
+            backend = "command"
+            command = ["wl-copy", "--trim-newline"]
+
+            [search]
+            result_count = 8
+
+            [keybindings]
+            preset = "vim"
+
+            [keybindings.list]
+            x = "toggle_favorite"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(OutputMode::Both, config.output);
+        assert_eq!(SkinTone::MediumDark, config.skin_tone);
+        assert_eq!(ClipboardBackend::Command, config.clipboard.backend);
+        assert_eq!(vec!["wl-copy", "--trim-newline"], config.clipboard.command);
+        assert_eq!(8, config.search.result_count);
+        assert_eq!(Config::default().search.tolerance, config.search.tolerance);
+        assert_eq!(Preset::Vim, config.keybindings.preset);
+        assert_eq!("toggle_favorite", config.keybindings.list["x"]);
+
+        // unknown keys and invalid values are rejected
+        assert!(Config::from_toml("colour = \"red\"").is_err());
+        assert!(Config::from_toml("output = \"printer\"").is_err());
+    }
+
+    #[test]
+    fn test_config_from_file() {
+        let dir = std::env::temp_dir().join(format!("emojicp-config-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        fs::create_dir_all(&dir).unwrap();
+
+        fs::write(&path, "output = \"both\"").unwrap();
+        assert_eq!(OutputMode::Both, Config::from_file(&path).unwrap().output);
+
+        // the TOML error is kept as the source
+        fs::write(&path, "colour = \"red\"").unwrap();
+        let error = Config::from_file(&path).unwrap_err();
+        let Error::InvalidConfig { source, .. } = error else {
+            panic!("{:?}", error);
+        };
+        assert!(source.message().contains("unknown field `colour`"));
+
+        // unknown keys and actions are caught while the file is loaded
+        fs::write(&path, "[keybindings.list]\nx = \"explode\"").unwrap();
+        assert!(matches!(
+            Config::from_file(&path),
+            Err(Error::InvalidConfigKeybindings {
+                source: KeyMapError::UnknownAction(_),
+                ..
+            })
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_config_keys() {
+        fn keys(prefix: &str, table: &toml::Table, found: &mut Vec<String>) {
+            for (key, value) in table {
+                let key = format!("{}{}", prefix, key);
+                match value {
+                    toml::Value::Table(table) if prefix.is_empty() => {
+                        keys(&format!("{}.", key), table, found)
+                    }
+                    _ => found.push(key),
+                }
+            }
+        }
+
+        // every key in the configuration file is documented
+        let config = Config {
+            height: Some(10),
+            max_version: Some(Version::default()),
+            theme: ThemeConfig {
+                input: Some(String::from("bold")),
+                list: Some(String::from("bold")),
+                highlight: Some(String::from("bold")),
+                borders: Some(String::from("bold")),
+                status: Some(String::from("bold")),
+                error: Some(String::from("bold")),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut found = Vec::new();
+        keys("", &toml::from_str(&config.to_toml()).unwrap(), &mut found);
+
+        let mut documented: Vec<String> =
+            CONFIG_KEYS.iter().map(|(key, _)| key.to_string()).collect();
+        found.sort();
+        documented.sort();
+        assert_eq!(documented, found);
+    }
+
+    #[test]
+    fn test_config_round_trip() {
+        let config = Config::default();
+        assert_eq!(config, Config::from_toml(&config.to_toml()).unwrap());
+    }
+
+    #[test]
+    fn test_config_precedence() {
+        let mut config = Config::from_toml("output = \"both\"\n[search]\ntolerance = 3").unwrap();
+
+        // environment variables override the file
+        config
+            .apply_env(env(&[
+                ("EMOJICP_OUTPUT", "stdout"),
+                ("EMOJICP_TOLERANCE", "4"),
+                ("EMOJICP_CLIPBOARD_COMMAND", "xclip -selection clipboard"),
+                ("EMOJICP_SEPARATOR", " "),
+                ("EMOJICP_KEEP_OPEN", "true"),
+                ("EMOJICP_HEIGHT", "12"),
+                ("EMOJICP_VIEW", "grid"),
+                ("EMOJICP_NAME_WIDTH", "30"),
+                ("EMOJICP_RENDER_CHECK", "false"),
+                ("EMOJICP_MAX_VERSION", "12.0"),
+                ("EMOJICP_COLOR", "never"),
+                ("EMOJICP_THEME", "high-contrast"),
+            ]))
+            .unwrap();
+        assert_eq!(OutputMode::Stdout, config.output);
+        assert_eq!(" ", config.separator);
+        assert!(config.keep_open);
+        assert_eq!(Some(12), config.height);
+        assert_eq!(View::Grid, config.view);
+        assert_eq!(30, config.name_width);
+        assert!(!config.render_check);
+        assert_eq!(Some("12.0".parse().unwrap()), config.max_version);
+        assert_eq!(ColorMode::Never, config.color);
+        assert_eq!(ThemeName::HighContrast, config.theme.name);
+        assert_eq!(4, config.search.tolerance);
+        assert_eq!(
+            vec!["xclip", "-selection", "clipboard"],
+            config.clipboard.command
+        );
+
+        // both spellings of a value are accepted
+        config
+            .apply_env(env(&[("EMOJICP_SKIN_TONE", "medium-dark")]))
+            .unwrap();
+        assert_eq!(SkinTone::MediumDark, config.skin_tone);
+        config
+            .apply_env(env(&[("EMOJICP_SKIN_TONE", "Medium_Light")]))
+            .unwrap();
+        assert_eq!(SkinTone::MediumLight, config.skin_tone);
+
+        // command-line flags override the environment
+        #[cfg(feature = "cli")]
+        {
+            config.apply_overrides(&ConfigOverrides {
+                output: Some(OutputMode::Clipboard),
+                ..Default::default()
+            });
+            assert_eq!(OutputMode::Clipboard, config.output);
+            assert_eq!(4, config.search.tolerance);
+            assert_eq!(Some(12), config.height);
+            assert!(config.keep_open);
+
+            // including settings the environment turned on or off
+            config.apply_overrides(&ConfigOverrides {
+                no_keep_open: true,
+                render_check: true,
+                ..Default::default()
+            });
+            assert!(!config.keep_open);
+            assert!(config.render_check);
+
+            // the last of a pair of flags wins
+            let args = Args::parse_from(["emojicp", "--no-keep-open", "--keep-open"]);
+            assert_eq!(Some(true), args.overrides.keep_open());
+            assert_eq!(None, args.overrides.render_check());
+        }
+
+        // invalid environment variables are reported
+        assert!(config
+            .apply_env(env(&[("EMOJICP_SKIN_TONE", "green")]))
+            .is_err());
+        assert!(config
+            .apply_env(env(&[("EMOJICP_RESULT_COUNT", "lots")]))
+            .is_err());
+        assert!(config
+            .apply_env(env(&[("EMOJICP_KEEP_OPEN", "maybe")]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_default_config_path() {
+        // `$XDG_CONFIG_HOME` takes precedence over `$HOME`
+        assert_eq!(
+            Some(PathBuf::from("/xdg/emojicp/config.toml")),
+            default_config_path(env(&[
+                ("XDG_CONFIG_HOME", "/xdg"),
+                ("HOME", "/home/ferris")
+            ]))
+        );
+
+        // relative `$XDG_CONFIG_HOME` values are ignored
+        assert_eq!(
+            Some(PathBuf::from("/home/ferris/.config/emojicp/config.toml")),
+            default_config_path(env(&[("XDG_CONFIG_HOME", "xdg"), ("HOME", "/home/ferris")]))
+        );
+
+        assert_eq!(None, default_config_path(env(&[])));
+    }
+
+    #[test]
+    fn test_default_data_dir() {
+        assert_eq!(
+            Some(PathBuf::from("/xdg/emojicp")),
+            default_data_dir(env(&[("XDG_DATA_HOME", "/xdg"), ("HOME", "/home/ferris")]))
+        );
+
+        assert_eq!(
+            Some(PathBuf::from("/home/ferris/.local/share/emojicp")),
+            default_data_dir(env(&[("HOME", "/home/ferris")]))
+        );
+    }
+}
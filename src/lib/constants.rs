@@ -4,12 +4,99 @@ pub const ABOUT_DESCRIPTION: &str = "\
     Run emojicp with the name of the emoji you'd like to copy to\n\
     your clipboard. If an emoji is found with that name, it will\n\
     be copied to your clipboard. If not, emojicp exits with status\n\
-    code 1.";
+    code 1.\n\n\
+    Settings are read from $XDG_CONFIG_HOME/emojicp/config.toml and\n\
+    can be overridden with EMOJICP_* environment variables or flags.";
 
 pub const SEARCH_PROMPT: &str = "Emoji you are searching for 🔍:";
 
 pub const EMOJI_TREE_FILE: &str = "static/emojitree.raw";
 
+pub const EMOJI_LIST_FILE: &str = "static/emojilist.raw";
+
+// How often the interactive UI wakes up when no input arrives
+pub const DEFAULT_TICK_RATE_MS: u64 = 250;
+
+// Maximum Levenshtein distance between the search term and a suggestion
+pub const DEFAULT_TOLERANCE: u32 = 10;
+
+// How many suggestions are shown to the user in interactive mode
+pub const DEFAULT_RESULT_COUNT: usize = 5;
+
+// Exit statuses, so that scripts can tell the different failures apart
+pub const EXIT_NOT_FOUND: u8 = 1;
+pub const EXIT_USAGE: u8 = 2;
+pub const EXIT_CLIPBOARD: u8 = 3;
+pub const EXIT_CONFIG: u8 = 4;
+pub const EXIT_IO: u8 = 5;
+pub const EXIT_DATA: u8 = 6;
+pub const EXIT_CANCELED: u8 = 130;
+
+// Terminal columns an emoji's name takes up when pairs are listed, before
+// the emoji itself
+pub const NAME_WIDTH: usize = 20;
+
+// The picker needs room for the search box and at least one suggestion when
+// it is drawn inline, beneath the shell prompt
+pub const MIN_INLINE_HEIGHT: u16 = 6;
+
+// The detail pane is shown next to the suggestions once the terminal is at
+// least `DETAILS_MIN_WIDTH` columns wide, taking up `DETAILS_WIDTH` of them
+pub const DETAILS_MIN_WIDTH: u16 = 80;
+pub const DETAILS_WIDTH: u16 = 36;
+
+// The help overlay is at most this many columns wide
+pub const HELP_WIDTH: u16 = 72;
+
+// Columns taken up by each emoji in the grid view, and how many search
+// results the grid shows at most
+pub const GRID_CELL_WIDTH: u16 = 4;
+pub const GRID_RESULT_COUNT: usize = 500;
+
+// How long a status message such as "copied 🦀" stays on screen
+pub const STATUS_DURATION_MS: u64 = 2000;
+
+// Two clicks on the same emoji at most this far apart make a double click
+pub const DOUBLE_CLICK_MS: u64 = 400;
+
+// The configuration file lives at `$XDG_CONFIG_HOME/emojicp/config.toml`,
+// falling back to `$HOME/.config/emojicp/config.toml`
+pub const CONFIG_DIR_NAME: &str = "emojicp";
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+// Favorites are kept in `$XDG_DATA_HOME/emojicp/favorites`, falling back to
+// `$HOME/.local/share/emojicp/favorites`
+pub const FAVORITES_FILE_NAME: &str = "favorites";
+
+// Searches that ended with an emoji being copied are remembered in
+// `$XDG_DATA_HOME/emojicp/history`, up to `HISTORY_SIZE` of them
+pub const HISTORY_FILE_NAME: &str = "history";
+pub const HISTORY_SIZE: usize = 100;
+
+// Environment variables that override the configuration file
+pub const ENV_CONFIG: &str = "EMOJICP_CONFIG";
+pub const ENV_OUTPUT: &str = "EMOJICP_OUTPUT";
+pub const ENV_CLIPBOARD_BACKEND: &str = "EMOJICP_CLIPBOARD_BACKEND";
+pub const ENV_CLIPBOARD_COMMAND: &str = "EMOJICP_CLIPBOARD_COMMAND";
+pub const ENV_SKIN_TONE: &str = "EMOJICP_SKIN_TONE";
+pub const ENV_SEPARATOR: &str = "EMOJICP_SEPARATOR";
+pub const ENV_KEEP_OPEN: &str = "EMOJICP_KEEP_OPEN";
+pub const ENV_HEIGHT: &str = "EMOJICP_HEIGHT";
+pub const ENV_VIEW: &str = "EMOJICP_VIEW";
+pub const ENV_NAME_WIDTH: &str = "EMOJICP_NAME_WIDTH";
+pub const ENV_RENDER_CHECK: &str = "EMOJICP_RENDER_CHECK";
+pub const ENV_MAX_VERSION: &str = "EMOJICP_MAX_VERSION";
+pub const ENV_COLOR: &str = "EMOJICP_COLOR";
+pub const ENV_THEME: &str = "EMOJICP_THEME";
+
+// Turns colors off when set to anything, see https://no-color.org
+pub const ENV_NO_COLOR: &str = "NO_COLOR";
+pub const ENV_PROMPT: &str = "EMOJICP_PROMPT";
+pub const ENV_TICK_RATE_MS: &str = "EMOJICP_TICK_RATE_MS";
+pub const ENV_TOLERANCE: &str = "EMOJICP_TOLERANCE";
+pub const ENV_RESULT_COUNT: &str = "EMOJICP_RESULT_COUNT";
+pub const ENV_KEYMAP: &str = "EMOJICP_KEYMAP";
+
 // Allow dead code because this static array is
 // only ever used by build.rs to generate the file
 // emojitree.raw which is embedded into the executable
@@ -27,7 +27,7 @@ mod tests {
         for pair in RAW_PAIRS {
             let result = tree.find_exact(&EmojiPair {
                 description: pair.0.to_string(),
-                emoji: "".to_string(),
+                ..Default::default()
             });
 
             let unwrapped_result = result.unwrap_or_else(|| panic!("found {} emoji", pair.1));
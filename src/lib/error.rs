@@ -0,0 +1,181 @@
+use std::io;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::constants::*;
+#[cfg(feature = "tui")]
+use crate::keymap::KeyMapError;
+use crate::pair::Version;
+use crate::query::ParseError;
+#[cfg(feature = "tui")]
+use crate::theme::ThemeError;
+
+// `Error` is every way `emojicp` can fail. Variants caused by another error
+// keep it as their source, so the whole chain can be inspected or reported
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("no emoji is named `{description}`")]
+    InvalidEmojiName { description: String },
+
+    #[error("no emoji matches `{query}`")]
+    NoMatches { query: String },
+
+    #[error("invalid search query")]
+    InvalidQuery {
+        #[source]
+        source: ParseError,
+    },
+
+    #[cfg(feature = "regex")]
+    #[error("invalid regular expression")]
+    InvalidRegex {
+        #[source]
+        source: regex::Error,
+    },
+
+    #[error("`{description}` was introduced in Emoji {version}, newer than {max_version}")]
+    UnsupportedEmoji {
+        description: String,
+        version: Version,
+        max_version: Version,
+    },
+
+    #[error("cannot open embedded file `{filename}`")]
+    MissingEmbeddedFile { filename: String },
+
+    #[error("cannot decode embedded file `{filename}`")]
+    CannotDecodeEmbeddedFile {
+        filename: String,
+        #[source]
+        source: bincode::Error,
+    },
+
+    #[error("cannot copy {emoji} to the clipboard")]
+    CannotCopyEmojiToClipboard {
+        emoji: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("cannot use the terminal")]
+    Terminal(#[source] io::Error),
+
+    #[error("cannot write the output")]
+    CannotWriteOutput(#[source] io::Error),
+
+    #[error("canceled; nothing was copied")]
+    UserCanceledProgram,
+
+    #[error("cannot read configuration file `{}`", path.display())]
+    CannotReadConfig {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[cfg(feature = "tui")]
+    #[error("invalid configuration file `{}`", path.display())]
+    InvalidConfig {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+
+    #[cfg(feature = "tui")]
+    #[error("invalid key bindings in configuration file `{}`", path.display())]
+    InvalidConfigKeybindings {
+        path: PathBuf,
+        #[source]
+        source: KeyMapError,
+    },
+
+    #[error("invalid value `{value}` for environment variable {name}")]
+    InvalidEnvironmentVariable { name: String, value: String },
+
+    #[error("configuration file `{}` already exists; use --force to overwrite it", path.display())]
+    ConfigAlreadyExists { path: PathBuf },
+
+    #[error("cannot write configuration file `{}`", path.display())]
+    CannotWriteConfig {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("cannot determine the configuration directory; set $XDG_CONFIG_HOME or $HOME")]
+    NoConfigDirectory,
+
+    #[cfg(feature = "tui")]
+    #[error("invalid key bindings")]
+    InvalidKeybindings {
+        #[source]
+        source: KeyMapError,
+    },
+
+    #[cfg(feature = "tui")]
+    #[error("invalid theme")]
+    InvalidTheme {
+        #[source]
+        source: ThemeError,
+    },
+}
+
+impl Error {
+    // `exit_code` is the status `emojicp` exits with when it fails with this
+    // error
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Error::InvalidEmojiName { .. }
+            | Error::NoMatches { .. }
+            | Error::UnsupportedEmoji { .. } => EXIT_NOT_FOUND,
+            Error::InvalidQuery { .. } => EXIT_USAGE,
+            #[cfg(feature = "regex")]
+            Error::InvalidRegex { .. } => EXIT_USAGE,
+            Error::CannotCopyEmojiToClipboard { .. } => EXIT_CLIPBOARD,
+            Error::CannotReadConfig { .. }
+            | Error::InvalidEnvironmentVariable { .. }
+            | Error::ConfigAlreadyExists { .. }
+            | Error::CannotWriteConfig { .. }
+            | Error::NoConfigDirectory => EXIT_CONFIG,
+            #[cfg(feature = "tui")]
+            Error::InvalidConfig { .. }
+            | Error::InvalidConfigKeybindings { .. }
+            | Error::InvalidKeybindings { .. }
+            | Error::InvalidTheme { .. } => EXIT_CONFIG,
+            Error::Terminal(_) | Error::CannotWriteOutput(_) => EXIT_IO,
+            Error::MissingEmbeddedFile { .. } | Error::CannotDecodeEmbeddedFile { .. } => EXIT_DATA,
+            Error::UserCanceledProgram => EXIT_CANCELED,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    #[test]
+    fn test_error_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<Error>();
+    }
+
+    #[test]
+    #[cfg(feature = "tui")]
+    fn test_error_source() {
+        use std::error::Error as _;
+        use std::io;
+        use std::path::Path;
+
+        use crate::config::Config;
+
+        let error = Config::from_file(Path::new("/nonexistent/emojicp.toml")).unwrap_err();
+        assert!(matches!(error, Error::CannotReadConfig { .. }));
+
+        // the underlying I/O error is kept
+        let source = error.source().unwrap();
+        let source = source.downcast_ref::<io::Error>().unwrap();
+        assert_eq!(io::ErrorKind::NotFound, source.kind());
+    }
+}
@@ -0,0 +1,110 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::config::default_data_dir;
+use crate::constants::FAVORITES_FILE_NAME;
+
+// `Favorites` is the list of emoji names the user has starred in the
+// interactive picker. It is stored one name per line in
+// `$XDG_DATA_HOME/emojicp/favorites`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Favorites {
+    // Where the favorites are saved. `None` if no data directory could be
+    // determined, in which case favorites only last for the current session
+    path: Option<PathBuf>,
+
+    names: Vec<String>,
+}
+
+impl Favorites {
+    // `load` reads the favorites from the default location. A missing or
+    // unreadable file simply means the user hasn't starred anything yet
+    pub fn load() -> Favorites {
+        let path =
+            default_data_dir(|name| env::var(name).ok()).map(|dir| dir.join(FAVORITES_FILE_NAME));
+
+        match path {
+            Some(path) => Favorites::from_file(path),
+            None => Favorites::default(),
+        }
+    }
+
+    pub fn from_file(path: PathBuf) -> Favorites {
+        let names = fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(String::from)
+            .collect();
+
+        Favorites {
+            path: Some(path),
+            names,
+        }
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.iter().any(|favorite| favorite == name)
+    }
+
+    // `toggle` stars `name` if it isn't a favorite yet and unstars it
+    // otherwise, then saves the favorites
+    pub fn toggle(&mut self, name: &str) -> io::Result<()> {
+        if self.contains(name) {
+            self.names.retain(|favorite| favorite != name);
+        } else {
+            self.names.push(name.to_owned());
+        }
+
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut contents = self.names.join("\n");
+        contents.push('\n');
+        fs::write(path, contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::favorites::Favorites;
+
+    #[test]
+    fn test_favorites_toggle() {
+        let dir = std::env::temp_dir().join(format!("emojicp-favorites-{}", std::process::id()));
+        let path = dir.join("favorites");
+
+        // a missing file means there are no favorites
+        let mut favorites = Favorites::from_file(path.clone());
+        assert!(favorites.names().is_empty());
+
+        favorites.toggle("crab").unwrap();
+        favorites.toggle("snake").unwrap();
+        favorites.toggle("crab").unwrap();
+        assert!(!favorites.contains("crab"));
+        assert!(favorites.contains("snake"));
+
+        // favorites are saved as they are toggled
+        assert_eq!(favorites, Favorites::from_file(path));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
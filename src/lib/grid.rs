@@ -0,0 +1,204 @@
+use crate::pair::EmojiPair;
+
+// The heading used for emojis that aren't listed under a Unicode group
+const OTHER_CATEGORY: &str = "Other";
+
+// One line of the grid: either the name of a category or a run of emojis
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Row {
+    Header(String),
+
+    // The emojis at indices `start..end` of the items
+    Cells { start: usize, end: usize },
+}
+
+// `Grid` lays items out in rows of `columns` emojis, starting a new row
+// under a category header whenever the category changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    pub columns: usize,
+    pub rows: Vec<Row>,
+}
+
+impl Grid {
+    pub fn new(items: &[EmojiPair], columns: usize) -> Grid {
+        let columns = columns.max(1);
+        let mut rows = Vec::new();
+        let mut start = 0;
+
+        while start < items.len() {
+            let category = category(&items[start]);
+            rows.push(Row::Header(category.to_owned()));
+
+            let end = start
+                + items[start..]
+                    .iter()
+                    .take_while(|pair| self::category(pair) == category)
+                    .count();
+
+            for row_start in (start..end).step_by(columns) {
+                rows.push(Row::Cells {
+                    start: row_start,
+                    end: (row_start + columns).min(end),
+                });
+            }
+
+            start = end;
+        }
+
+        Grid { columns, rows }
+    }
+
+    // `row_of` returns the index of the row holding item `index`
+    pub fn row_of(&self, index: usize) -> Option<usize> {
+        self.rows.iter().position(|row| match row {
+            Row::Cells { start, end } => (*start..*end).contains(&index),
+            Row::Header(_) => false,
+        })
+    }
+
+    // `below` returns the item in the same column of the next row of
+    // emojis, or the last one in that row if it is shorter. `None` if `index`
+    // is already in the last row
+    pub fn below(&self, index: usize) -> Option<usize> {
+        let row = self.row_of(index)?;
+        nearest(self.column(index), self.rows[row + 1..].iter())
+    }
+
+    // `above` is like `below` for the previous row of emojis
+    pub fn above(&self, index: usize) -> Option<usize> {
+        let row = self.row_of(index)?;
+        nearest(self.column(index), self.rows[..row].iter().rev())
+    }
+
+    // `categories` returns the index of the first emoji of each category
+    pub fn categories(&self) -> Vec<usize> {
+        self.rows
+            .windows(2)
+            .filter_map(|rows| match rows {
+                [Row::Header(_), Row::Cells { start, .. }] => Some(*start),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn column(&self, index: usize) -> usize {
+        match self.row_of(index).map(|row| &self.rows[row]) {
+            Some(Row::Cells { start, .. }) => index - start,
+            _ => 0,
+        }
+    }
+}
+
+// `nearest` finds the item in `column` of the first row of emojis in `rows`
+fn nearest<'a>(column: usize, mut rows: impl Iterator<Item = &'a Row>) -> Option<usize> {
+    rows.find_map(|row| match row {
+        Row::Cells { start, end } => Some((start + column).min(end - 1)),
+        Row::Header(_) => None,
+    })
+}
+
+fn category(pair: &EmojiPair) -> &str {
+    if pair.category.is_empty() {
+        OTHER_CATEGORY
+    } else {
+        &pair.category
+    }
+}
+
+// `by_category` orders `pairs` so that emojis of the same category are next
+// to each other. Categories keep the order they first appear in, and so do
+// the emojis within each of them
+pub fn by_category(pairs: &[EmojiPair]) -> Vec<EmojiPair> {
+    let mut categories: Vec<&str> = Vec::new();
+    for pair in pairs {
+        if !categories.contains(&category(pair)) {
+            categories.push(category(pair));
+        }
+    }
+
+    let mut ordered = pairs.to_vec();
+    ordered.sort_by_key(|pair| categories.iter().position(|known| *known == category(pair)));
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::{by_category, Grid, Row};
+    use crate::pair::EmojiPair;
+
+    fn pair(description: &str, category: &str) -> EmojiPair {
+        EmojiPair {
+            description: String::from(description),
+            category: String::from(category),
+            ..Default::default()
+        }
+    }
+
+    fn pairs() -> Vec<EmojiPair> {
+        vec![
+            pair("crab", "Animals & Nature"),
+            pair("snake", "Animals & Nature"),
+            pair("monkey", "Animals & Nature"),
+            pair("coffee", "Food & Drink"),
+            pair("pizza", "Food & Drink"),
+            pair("hooray", ""),
+        ]
+    }
+
+    #[test]
+    fn test_grid_rows() {
+        let grid = Grid::new(&pairs(), 2);
+
+        assert_eq!(
+            vec![
+                Row::Header(String::from("Animals & Nature")),
+                Row::Cells { start: 0, end: 2 },
+                Row::Cells { start: 2, end: 3 },
+                Row::Header(String::from("Food & Drink")),
+                Row::Cells { start: 3, end: 5 },
+                Row::Header(String::from("Other")),
+                Row::Cells { start: 5, end: 6 },
+            ],
+            grid.rows
+        );
+        assert_eq!(Some(4), grid.row_of(3));
+        assert_eq!(None, grid.row_of(6));
+        assert_eq!(vec![0, 3, 5], grid.categories());
+    }
+
+    #[test]
+    fn test_grid_navigation() {
+        let grid = Grid::new(&pairs(), 2);
+
+        // moving down keeps the column, across category headers too
+        assert_eq!(Some(2), grid.below(0));
+        assert_eq!(Some(3), grid.below(2));
+        assert_eq!(Some(5), grid.below(4));
+        assert_eq!(None, grid.below(5));
+
+        // a shorter row puts the highlight on its last emoji
+        assert_eq!(Some(2), grid.below(1));
+        assert_eq!(Some(5), grid.above(5).and_then(|i| grid.below(i)));
+
+        assert_eq!(Some(2), grid.above(4));
+        assert_eq!(Some(0), grid.above(2));
+        assert_eq!(None, grid.above(1));
+    }
+
+    #[test]
+    fn test_by_category() {
+        let mixed = vec![
+            pair("crab", "Animals & Nature"),
+            pair("coffee", "Food & Drink"),
+            pair("snake", "Animals & Nature"),
+            pair("pizza", "Food & Drink"),
+        ];
+
+        let names: Vec<String> = by_category(&mixed)
+            .into_iter()
+            .map(|pair| pair.description)
+            .collect();
+        assert_eq!(vec!["crab", "snake", "coffee", "pizza"], names);
+    }
+}
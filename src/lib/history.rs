@@ -0,0 +1,115 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::config::default_data_dir;
+use crate::constants::{HISTORY_FILE_NAME, HISTORY_SIZE};
+
+// `History` is the list of searches that ended with the user copying an
+// emoji, oldest first. It is stored one search per line in
+// `$XDG_DATA_HOME/emojicp/history`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct History {
+    // Where the history is saved. `None` if no data directory could be
+    // determined, in which case the history only lasts for the current session
+    path: Option<PathBuf>,
+
+    entries: Vec<String>,
+}
+
+impl History {
+    // `load` reads the history from the default location. A missing or
+    // unreadable file simply means there is no history yet
+    pub fn load() -> History {
+        let path =
+            default_data_dir(|name| env::var(name).ok()).map(|dir| dir.join(HISTORY_FILE_NAME));
+
+        match path {
+            Some(path) => History::from_file(path),
+            None => History::default(),
+        }
+    }
+
+    pub fn from_file(path: PathBuf) -> History {
+        let entries = fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .filter(|entry| !entry.trim().is_empty())
+            .map(String::from)
+            .collect();
+
+        History {
+            path: Some(path),
+            entries,
+        }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    // `push` records `entry` as the most recent search, dropping any older
+    // copy of it and the oldest searches beyond `HISTORY_SIZE`, then saves
+    // the history
+    pub fn push(&mut self, entry: &str) -> io::Result<()> {
+        if entry.trim().is_empty() {
+            return Ok(());
+        }
+
+        self.entries.retain(|existing| existing != entry);
+        self.entries.push(entry.to_owned());
+
+        let overflow = self.entries.len().saturating_sub(HISTORY_SIZE);
+        self.entries.drain(..overflow);
+
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut contents = self.entries.join("\n");
+        contents.push('\n');
+        fs::write(path, contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::constants::HISTORY_SIZE;
+    use crate::history::History;
+
+    #[test]
+    fn test_history_push() {
+        let dir = std::env::temp_dir().join(format!("emojicp-history-{}", std::process::id()));
+        let path = dir.join("history");
+
+        let mut history = History::from_file(path.clone());
+        history.push("crab").unwrap();
+        history.push("snake").unwrap();
+        history.push("  ").unwrap();
+
+        // repeated searches move to the end instead of being duplicated
+        history.push("crab").unwrap();
+        assert_eq!(["snake", "crab"], history.entries());
+        assert_eq!(history, History::from_file(path));
+
+        // only the most recent searches are kept
+        for i in 0..HISTORY_SIZE {
+            history.push(&i.to_string()).unwrap();
+        }
+        assert_eq!(HISTORY_SIZE, history.entries().len());
+        assert_eq!("0", history.entries()[0]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,338 @@
+use std::io;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::history::History;
+
+// `LineInput` is a single-line text field with readline-style editing. The
+// cursor is a byte offset into `text` that always sits on a grapheme cluster
+// boundary, so accented letters, emojis and other multi-byte characters are
+// moved over and deleted as a whole
+#[derive(Debug, Clone, Default)]
+pub struct LineInput {
+    text: String,
+    cursor: usize,
+
+    // Searches the user can recall with `previous_history`/`next_history`
+    history: History,
+
+    // Which history entry is being shown. `None` while editing a new line
+    history_index: Option<usize>,
+
+    // The new line that was being edited before browsing the history
+    draft: String,
+}
+
+impl LineInput {
+    pub fn new(history: History) -> Self {
+        LineInput {
+            history,
+            ..Default::default()
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    // `cursor_column` is how many terminal columns the text before the
+    // cursor takes up, e.g. 2 for a single wide emoji
+    pub fn cursor_column(&self) -> usize {
+        self.text[..self.cursor].width()
+    }
+
+    pub fn insert_char(&mut self, new_char: char) {
+        self.edited();
+        self.text.insert(self.cursor, new_char);
+        self.cursor += new_char.len_utf8();
+    }
+
+    // `insert_str` inserts pasted text at the cursor. The input is a single
+    // line, so line breaks become spaces and other control characters are
+    // dropped
+    pub fn insert_str(&mut self, pasted: &str) {
+        let pasted: String = pasted
+            .chars()
+            .map(|c| if c == '\n' || c == '\r' { ' ' } else { c })
+            .filter(|c| !c.is_control())
+            .collect();
+
+        self.edited();
+        self.text.insert_str(self.cursor, &pasted);
+        self.cursor += pasted.len();
+    }
+
+    pub fn backward_char(&mut self) {
+        self.cursor = self.previous_boundary(self.cursor);
+    }
+
+    pub fn forward_char(&mut self) {
+        self.cursor = self.next_boundary(self.cursor);
+    }
+
+    // `backward_word` moves to the start of the current or previous word.
+    // Words are runs of letters and digits, so `_` in emoji names separates
+    // words just like spaces do
+    pub fn backward_word(&mut self) {
+        self.cursor = self.previous_word_boundary();
+    }
+
+    // `forward_word` moves to the end of the current or next word
+    pub fn forward_word(&mut self) {
+        let mut cursor = self.cursor;
+        while cursor < self.text.len() && !self.is_word_at(cursor) {
+            cursor = self.next_boundary(cursor);
+        }
+        while cursor < self.text.len() && self.is_word_at(cursor) {
+            cursor = self.next_boundary(cursor);
+        }
+
+        self.cursor = cursor;
+    }
+
+    pub fn beginning_of_line(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn end_of_line(&mut self) {
+        self.cursor = self.text.len();
+    }
+
+    // `delete_char` deletes the character under the cursor
+    pub fn delete_char(&mut self) {
+        let end = self.next_boundary(self.cursor);
+        self.delete(self.cursor, end);
+    }
+
+    // `backward_delete_char` deletes the character before the cursor
+    pub fn backward_delete_char(&mut self) {
+        let start = self.previous_boundary(self.cursor);
+        self.delete(start, self.cursor);
+    }
+
+    pub fn backward_kill_word(&mut self) {
+        let start = self.previous_word_boundary();
+        self.delete(start, self.cursor);
+    }
+
+    // `backward_kill_line` deletes everything before the cursor
+    pub fn backward_kill_line(&mut self) {
+        self.delete(0, self.cursor);
+    }
+
+    // `kill_line` deletes everything from the cursor to the end of the line
+    pub fn kill_line(&mut self) {
+        self.delete(self.cursor, self.text.len());
+    }
+
+    pub fn clear(&mut self) {
+        self.delete(0, self.text.len());
+    }
+
+    // `previous_history` replaces the line with the previous search from the
+    // history, remembering the line being edited so `next_history` can
+    // return to it
+    pub fn previous_history(&mut self) {
+        let index = match self.history_index {
+            None if self.history.entries().is_empty() => return,
+            None => {
+                self.draft = self.text.clone();
+                self.history.entries().len() - 1
+            }
+            Some(0) => return,
+            Some(index) => index - 1,
+        };
+
+        self.history_index = Some(index);
+        self.replace(self.history.entries()[index].clone());
+    }
+
+    pub fn next_history(&mut self) {
+        let Some(index) = self.history_index else {
+            return;
+        };
+
+        if index + 1 < self.history.entries().len() {
+            self.history_index = Some(index + 1);
+            self.replace(self.history.entries()[index + 1].clone());
+        } else {
+            let draft = std::mem::take(&mut self.draft);
+            self.history_index = None;
+            self.replace(draft);
+        }
+    }
+
+    // `remember` adds the current line to the history
+    pub fn remember(&mut self) -> io::Result<()> {
+        self.history.push(&self.text)
+    }
+
+    fn replace(&mut self, text: String) {
+        self.text = text;
+        self.cursor = self.text.len();
+    }
+
+    fn delete(&mut self, start: usize, end: usize) {
+        if start < end {
+            self.edited();
+            self.text.replace_range(start..end, "");
+            self.cursor = start;
+        }
+    }
+
+    // Editing a line recalled from the history turns it into a new line
+    fn edited(&mut self) {
+        self.history_index = None;
+    }
+
+    fn previous_boundary(&self, cursor: usize) -> usize {
+        self.text[..cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    }
+
+    fn next_boundary(&self, cursor: usize) -> usize {
+        self.text[cursor..]
+            .graphemes(true)
+            .next()
+            .map(|grapheme| cursor + grapheme.len())
+            .unwrap_or(self.text.len())
+    }
+
+    fn previous_word_boundary(&self) -> usize {
+        let mut cursor = self.cursor;
+        while cursor > 0 && !self.is_word_at(self.previous_boundary(cursor)) {
+            cursor = self.previous_boundary(cursor);
+        }
+        while cursor > 0 && self.is_word_at(self.previous_boundary(cursor)) {
+            cursor = self.previous_boundary(cursor);
+        }
+
+        cursor
+    }
+
+    fn is_word_at(&self, cursor: usize) -> bool {
+        self.text[cursor..]
+            .graphemes(true)
+            .next()
+            .map(|grapheme| grapheme.chars().any(char::is_alphanumeric))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::history::History;
+    use crate::input::LineInput;
+
+    fn input(text: &str) -> LineInput {
+        let mut input = LineInput::default();
+        input.insert_str(text);
+        input
+    }
+
+    #[test]
+    fn test_line_input_non_ascii() {
+        // a combining accent, a wide character and an emoji with a skin tone
+        let mut line = input("cafe\u{301}");
+        line.insert_char('🦀');
+        assert_eq!(6, line.cursor_column());
+        line.insert_str("👋🏽");
+        assert_eq!("cafe\u{301}🦀👋🏽", line.text());
+
+        // each grapheme is moved over and deleted as a whole
+        line.backward_char();
+        line.backward_char();
+        assert_eq!(4, line.cursor_column());
+        line.backward_delete_char();
+        assert_eq!("caf🦀👋🏽", line.text());
+        line.delete_char();
+        assert_eq!("caf👋🏽", line.text());
+
+        line.end_of_line();
+        line.backward_delete_char();
+        assert_eq!("caf", line.text());
+        line.forward_char();
+        assert_eq!(3, line.cursor_column());
+    }
+
+    #[test]
+    fn test_line_input_words() {
+        let mut line = input("red_haired woman");
+
+        line.backward_word();
+        assert_eq!(11, line.cursor_column());
+        line.backward_word();
+        assert_eq!(4, line.cursor_column());
+        line.forward_word();
+        assert_eq!(10, line.cursor_column());
+
+        line.end_of_line();
+        line.backward_kill_word();
+        assert_eq!("red_haired ", line.text());
+        line.backward_kill_word();
+        assert_eq!("red_", line.text());
+    }
+
+    #[test]
+    fn test_line_input_kill() {
+        let mut line = input("thumbsup");
+        line.beginning_of_line();
+        line.forward_word();
+        line.kill_line();
+        assert_eq!("thumbsup", line.text());
+
+        line.beginning_of_line();
+        for _ in 0.."thumbs".len() {
+            line.forward_char();
+        }
+        line.kill_line();
+        assert_eq!("thumbs", line.text());
+
+        line.backward_char();
+        line.backward_kill_line();
+        assert_eq!("s", line.text());
+        assert_eq!(0, line.cursor_column());
+    }
+
+    #[test]
+    fn test_line_input_paste() {
+        let mut line = input("party");
+        line.beginning_of_line();
+        line.insert_str("tada\n\u{7}");
+        assert_eq!("tada party", line.text());
+        assert_eq!(5, line.cursor_column());
+    }
+
+    #[test]
+    fn test_line_input_history() {
+        let mut line = LineInput::new(History::default());
+        line.insert_str("crab");
+        line.remember().unwrap();
+        line.clear();
+        line.insert_str("snake");
+        line.remember().unwrap();
+        line.clear();
+
+        line.insert_str("mon");
+        line.previous_history();
+        assert_eq!("snake", line.text());
+        line.previous_history();
+        line.previous_history();
+        assert_eq!("crab", line.text());
+
+        line.next_history();
+        assert_eq!("snake", line.text());
+        line.next_history();
+        assert_eq!("mon", line.text());
+        line.next_history();
+        assert_eq!("mon", line.text());
+    }
+}
@@ -0,0 +1,759 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+// What is wrong with a key binding, e.g. in the `[keybindings]` section of
+// the configuration file
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum KeyMapError {
+    #[error("unknown key `{0}`")]
+    UnknownKey(String),
+
+    #[error("unknown action `{0}`")]
+    UnknownAction(String),
+}
+
+// Everything the user can do in the interactive picker. Keys are bound to
+// actions through a `KeyMap`, so the event loop never looks at raw keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Action {
+    SelectNext,
+    SelectPrev,
+    SelectLeft,
+    SelectRight,
+    Unselect,
+    Copy,
+    CopyAndStay,
+    Cancel,
+    ClearQuery,
+    BackwardDeleteChar,
+    ToggleFavorite,
+    ToggleMark,
+    ToggleDetails,
+    ToggleView,
+    #[cfg(feature = "regex")]
+    ToggleRegex,
+    ShowHelp,
+    BackwardChar,
+    ForwardChar,
+    BackwardWord,
+    ForwardWord,
+    BeginningOfLine,
+    EndOfLine,
+    DeleteChar,
+    BackwardKillWord,
+    BackwardKillLine,
+    KillLine,
+    PreviousHistory,
+    NextHistory,
+}
+
+// The name used for each action in the configuration file, followed by a
+// short description shown to the user
+const ACTIONS: &[(Action, &str, &str)] = &[
+    (
+        Action::SelectNext,
+        "select_next",
+        "Highlight the next suggestion",
+    ),
+    (
+        Action::SelectPrev,
+        "select_prev",
+        "Highlight the previous suggestion",
+    ),
+    (
+        Action::SelectLeft,
+        "select_left",
+        "Highlight the emoji to the left in the grid",
+    ),
+    (
+        Action::SelectRight,
+        "select_right",
+        "Highlight the emoji to the right in the grid",
+    ),
+    (Action::Unselect, "unselect", "Return to the search box"),
+    (Action::Copy, "copy", "Copy the highlighted emoji and exit"),
+    (
+        Action::CopyAndStay,
+        "copy_and_stay",
+        "Copy the highlighted emoji and keep the picker open",
+    ),
+    (Action::Cancel, "cancel", "Exit without copying anything"),
+    (Action::ClearQuery, "clear_query", "Clear the search box"),
+    (
+        Action::BackwardDeleteChar,
+        "backward_delete_char",
+        "Delete the character before the cursor",
+    ),
+    (
+        Action::ToggleFavorite,
+        "toggle_favorite",
+        "Add or remove the highlighted emoji from your favorites",
+    ),
+    (
+        Action::ToggleMark,
+        "toggle_mark",
+        "Mark or unmark the highlighted emoji to copy several at once",
+    ),
+    (
+        Action::ToggleDetails,
+        "toggle_details",
+        "Show or hide the details of the highlighted emoji",
+    ),
+    (
+        Action::ToggleView,
+        "toggle_view",
+        "Switch between the list and the grid of emojis",
+    ),
+    #[cfg(feature = "regex")]
+    (
+        Action::ToggleRegex,
+        "toggle_regex",
+        "Switch between search queries and regular expressions",
+    ),
+    (
+        Action::ShowHelp,
+        "show_help",
+        "Show the key bindings and how to search",
+    ),
+    (
+        Action::BackwardChar,
+        "backward_char",
+        "Move the cursor one character left",
+    ),
+    (
+        Action::ForwardChar,
+        "forward_char",
+        "Move the cursor one character right",
+    ),
+    (
+        Action::BackwardWord,
+        "backward_word",
+        "Move the cursor to the previous word",
+    ),
+    (
+        Action::ForwardWord,
+        "forward_word",
+        "Move the cursor to the end of the next word",
+    ),
+    (
+        Action::BeginningOfLine,
+        "beginning_of_line",
+        "Move the cursor to the start of the search",
+    ),
+    (
+        Action::EndOfLine,
+        "end_of_line",
+        "Move the cursor to the end of the search",
+    ),
+    (
+        Action::DeleteChar,
+        "delete_char",
+        "Delete the character under the cursor",
+    ),
+    (
+        Action::BackwardKillWord,
+        "backward_kill_word",
+        "Delete the word before the cursor",
+    ),
+    (
+        Action::BackwardKillLine,
+        "backward_kill_line",
+        "Delete everything before the cursor",
+    ),
+    (
+        Action::KillLine,
+        "kill_line",
+        "Delete everything after the cursor",
+    ),
+    (
+        Action::PreviousHistory,
+        "previous_history",
+        "Recall the previous search",
+    ),
+    (
+        Action::NextHistory,
+        "next_history",
+        "Recall the next search",
+    ),
+];
+
+impl Action {
+    pub fn name(self) -> &'static str {
+        ACTIONS
+            .iter()
+            .find(|(action, _, _)| *action == self)
+            .map(|(_, name, _)| *name)
+            .expect("every action has a name")
+    }
+
+    pub fn description(self) -> &'static str {
+        ACTIONS
+            .iter()
+            .find(|(action, _, _)| *action == self)
+            .map(|(_, _, description)| *description)
+            .expect("every action has a description")
+    }
+}
+
+impl FromStr for Action {
+    type Err = KeyMapError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        ACTIONS
+            .iter()
+            .find(|(_, action_name, _)| *action_name == name)
+            .map(|(action, _, _)| *action)
+            .ok_or_else(|| KeyMapError::UnknownAction(name.to_owned()))
+    }
+}
+
+// Where the keyboard focus is when a key is pressed. Bindings in `Global`
+// apply everywhere unless the focused context binds the same key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Context {
+    Global,
+
+    // The search box has focus; unbound printable keys are typed into it
+    Input,
+
+    // The list of suggestions has focus
+    List,
+
+    // The grid of emojis has focus
+    Grid,
+}
+
+impl Context {
+    pub fn name(self) -> &'static str {
+        match self {
+            Context::Global => "global",
+            Context::Input => "input",
+            Context::List => "list",
+            Context::Grid => "grid",
+        }
+    }
+
+    // `description` says when bindings in the context apply
+    pub fn description(self) -> &'static str {
+        match self {
+            Context::Global => "Everywhere",
+            Context::Input => "While typing in the search box",
+            Context::List => "While a suggestion is highlighted",
+            Context::Grid => "While an emoji in the grid is highlighted",
+        }
+    }
+}
+
+// The built-in sets of key bindings that user bindings are layered on top of
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    #[default]
+    Default,
+    Vim,
+    Emacs,
+}
+
+const DEFAULT_BINDINGS: &[(Context, &str, Action)] = &[
+    (Context::Global, "ctrl-c", Action::Cancel),
+    (Context::Global, "esc", Action::Cancel),
+    (Context::Global, "down", Action::SelectNext),
+    (Context::Global, "up", Action::SelectPrev),
+    (Context::Global, "enter", Action::Copy),
+    (Context::Global, "ctrl-y", Action::CopyAndStay),
+    (Context::Global, "backspace", Action::BackwardDeleteChar),
+    (Context::Global, "ctrl-l", Action::ClearQuery),
+    (Context::Global, "ctrl-t", Action::ToggleFavorite),
+    (Context::Global, "tab", Action::ToggleMark),
+    (Context::Global, "ctrl-o", Action::ToggleDetails),
+    (Context::Global, "f2", Action::ToggleView),
+    #[cfg(feature = "regex")]
+    (Context::Global, "ctrl-r", Action::ToggleRegex),
+    (Context::Global, "f1", Action::ShowHelp),
+    (Context::Global, "left", Action::BackwardChar),
+    (Context::Global, "right", Action::ForwardChar),
+    (Context::Global, "ctrl-left", Action::BackwardWord),
+    (Context::Global, "alt-b", Action::BackwardWord),
+    (Context::Global, "ctrl-right", Action::ForwardWord),
+    (Context::Global, "alt-f", Action::ForwardWord),
+    (Context::Global, "home", Action::BeginningOfLine),
+    (Context::Global, "ctrl-a", Action::BeginningOfLine),
+    (Context::Global, "end", Action::EndOfLine),
+    (Context::Global, "ctrl-e", Action::EndOfLine),
+    (Context::Global, "delete", Action::DeleteChar),
+    (Context::Global, "ctrl-d", Action::DeleteChar),
+    (Context::Global, "ctrl-w", Action::BackwardKillWord),
+    (Context::Global, "alt-backspace", Action::BackwardKillWord),
+    (Context::Global, "ctrl-u", Action::BackwardKillLine),
+    (Context::Global, "ctrl-k", Action::KillLine),
+    (Context::Global, "alt-up", Action::PreviousHistory),
+    (Context::Global, "alt-p", Action::PreviousHistory),
+    (Context::Global, "alt-down", Action::NextHistory),
+    (Context::Global, "alt-n", Action::NextHistory),
+    (Context::List, "left", Action::Unselect),
+    (Context::List, "space", Action::ToggleMark),
+    (Context::List, "?", Action::ShowHelp),
+    (Context::Grid, "left", Action::SelectLeft),
+    (Context::Grid, "right", Action::SelectRight),
+    (Context::Grid, "space", Action::ToggleMark),
+    (Context::Grid, "?", Action::ShowHelp),
+];
+
+const VIM_BINDINGS: &[(Context, &str, Action)] = &[
+    (Context::Global, "ctrl-n", Action::SelectNext),
+    (Context::Global, "ctrl-p", Action::SelectPrev),
+    (Context::List, "j", Action::SelectNext),
+    (Context::List, "k", Action::SelectPrev),
+    (Context::List, "h", Action::Unselect),
+    (Context::List, "i", Action::Unselect),
+    (Context::List, "l", Action::Copy),
+    (Context::List, "f", Action::ToggleFavorite),
+    (Context::Grid, "j", Action::SelectNext),
+    (Context::Grid, "k", Action::SelectPrev),
+    (Context::Grid, "h", Action::SelectLeft),
+    (Context::Grid, "l", Action::SelectRight),
+    (Context::Grid, "i", Action::Unselect),
+    (Context::Grid, "f", Action::ToggleFavorite),
+];
+
+const EMACS_BINDINGS: &[(Context, &str, Action)] = &[
+    (Context::Global, "ctrl-n", Action::SelectNext),
+    (Context::Global, "ctrl-p", Action::SelectPrev),
+    (Context::Global, "ctrl-g", Action::Cancel),
+    (Context::Global, "ctrl-j", Action::Copy),
+    (Context::Global, "ctrl-h", Action::BackwardDeleteChar),
+    (Context::Global, "ctrl-b", Action::BackwardChar),
+    (Context::Global, "ctrl-f", Action::ForwardChar),
+];
+
+impl Preset {
+    fn bindings(self) -> impl Iterator<Item = &'static (Context, &'static str, Action)> {
+        let preset: &[(Context, &str, Action)] = match self {
+            Preset::Default => &[],
+            Preset::Vim => VIM_BINDINGS,
+            Preset::Emacs => EMACS_BINDINGS,
+        };
+
+        DEFAULT_BINDINGS.iter().chain(preset)
+    }
+}
+
+// The `[keybindings]` section of the configuration file. Each table maps a
+// key such as `"ctrl-j"` to the name of an action, or to `"none"` to remove
+// a binding inherited from the preset
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeybindingsConfig {
+    pub preset: Preset,
+    pub global: BTreeMap<String, String>,
+    pub input: BTreeMap<String, String>,
+    pub list: BTreeMap<String, String>,
+    pub grid: BTreeMap<String, String>,
+}
+
+// A key together with the modifiers held down while pressing it, written as
+// e.g. `ctrl-c`, `alt-enter`, `shift-tab`, `f1` or `j`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Terminals report `J` as shift + `J` and back-tab as shift +
+        // back-tab; the shift is already part of the key, so drop it to
+        // make the binding match however the key was written
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+
+        KeyBinding { code, modifiers }
+    }
+}
+
+impl From<KeyEvent> for KeyBinding {
+    fn from(event: KeyEvent) -> Self {
+        KeyBinding::new(event.code, event.modifiers)
+    }
+}
+
+const NAMED_KEYS: &[(KeyCode, &str)] = &[
+    (KeyCode::Backspace, "backspace"),
+    (KeyCode::Enter, "enter"),
+    (KeyCode::Left, "left"),
+    (KeyCode::Right, "right"),
+    (KeyCode::Up, "up"),
+    (KeyCode::Down, "down"),
+    (KeyCode::Home, "home"),
+    (KeyCode::End, "end"),
+    (KeyCode::PageUp, "pageup"),
+    (KeyCode::PageDown, "pagedown"),
+    (KeyCode::Tab, "tab"),
+    (KeyCode::BackTab, "backtab"),
+    (KeyCode::Delete, "delete"),
+    (KeyCode::Insert, "insert"),
+    (KeyCode::Esc, "esc"),
+    (KeyCode::Char(' '), "space"),
+];
+
+const MODIFIERS: &[(KeyModifiers, &str)] = &[
+    (KeyModifiers::CONTROL, "ctrl-"),
+    (KeyModifiers::ALT, "alt-"),
+    (KeyModifiers::SHIFT, "shift-"),
+];
+
+impl FromStr for KeyBinding {
+    type Err = KeyMapError;
+
+    fn from_str(binding: &str) -> Result<Self, Self::Err> {
+        let invalid = || KeyMapError::UnknownKey(binding.to_owned());
+
+        // Modifiers and key names are case-insensitive, but single characters
+        // keep their case so that e.g. `J` can be bound separately from `j`
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = binding.trim();
+        while let Some((modifier, prefix)) = MODIFIERS.iter().find(|(_, prefix)| {
+            rest.len() > prefix.len()
+                && rest
+                    .get(..prefix.len())
+                    .is_some_and(|p| p.eq_ignore_ascii_case(prefix))
+        }) {
+            modifiers |= *modifier;
+            rest = &rest[prefix.len()..];
+        }
+
+        let name = rest.to_lowercase();
+        let code = if let Some((code, _)) = NAMED_KEYS.iter().find(|(_, key)| *key == name) {
+            *code
+        } else if let Some(number) = name.strip_prefix('f').filter(|n| !n.is_empty()) {
+            KeyCode::F(number.parse().map_err(|_| invalid())?)
+        } else {
+            let mut chars = rest.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => return Err(invalid()),
+            }
+        };
+
+        Ok(KeyBinding::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (modifier, prefix) in MODIFIERS {
+            if self.modifiers.contains(*modifier) {
+                write!(f, "{}", prefix)?;
+            }
+        }
+
+        match NAMED_KEYS.iter().find(|(code, _)| *code == self.code) {
+            Some((_, name)) => write!(f, "{}", name),
+            None => match self.code {
+                KeyCode::F(number) => write!(f, "f{}", number),
+                KeyCode::Char(c) => write!(f, "{}", c),
+                code => write!(f, "{:?}", code),
+            },
+        }
+    }
+}
+
+// `KeyMap` resolves key presses to actions for each focus context
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyMap {
+    bindings: HashMap<(Context, KeyBinding), Action>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        KeyMap::new(&KeybindingsConfig::default()).expect("built-in bindings are valid")
+    }
+}
+
+impl KeyMap {
+    // `new` starts from the bindings of the configured preset and applies
+    // the user's bindings on top of them
+    pub fn new(config: &KeybindingsConfig) -> Result<KeyMap, KeyMapError> {
+        let mut bindings = HashMap::new();
+
+        for (context, key, action) in config.preset.bindings() {
+            bindings.insert((*context, key.parse()?), *action);
+        }
+
+        let overrides = [
+            (Context::Global, &config.global),
+            (Context::Input, &config.input),
+            (Context::List, &config.list),
+            (Context::Grid, &config.grid),
+        ];
+
+        for (context, table) in overrides {
+            for (key, action) in table {
+                let key: KeyBinding = key.parse()?;
+                if action == "none" {
+                    bindings.remove(&(context, key));
+                } else {
+                    bindings.insert((context, key), action.parse()?);
+                }
+            }
+        }
+
+        Ok(KeyMap { bindings })
+    }
+
+    // `action` returns what pressing `key` does while `context` has focus
+    pub fn action(&self, context: Context, key: KeyBinding) -> Option<Action> {
+        self.bindings
+            .get(&(context, key))
+            .or_else(|| self.bindings.get(&(Context::Global, key)))
+            .copied()
+    }
+
+    // `keys` lists the keys that perform `action` while `context` has focus,
+    // including global keys the context doesn't bind to something else
+    pub fn keys(&self, context: Context, action: Action) -> Vec<KeyBinding> {
+        let mut keys: Vec<KeyBinding> = self
+            .bindings
+            .keys()
+            .filter(|(_, key)| self.action(context, *key) == Some(action))
+            .map(|(_, key)| *key)
+            .collect();
+
+        keys.sort_by_key(KeyBinding::to_string);
+        keys.dedup();
+        keys
+    }
+
+    // `bindings` lists every binding, ordered by context and then action
+    pub fn bindings(&self) -> Vec<(Context, KeyBinding, Action)> {
+        let mut bindings: Vec<(Context, KeyBinding, Action)> = self
+            .bindings
+            .iter()
+            .map(|((context, key), action)| (*context, *key, *action))
+            .collect();
+
+        bindings.sort_by_key(|(context, key, action)| (*context, *action, key.to_string()));
+        bindings
+    }
+
+    // `grouped` lists the keys bound to each action, ordered by context and
+    // then action
+    pub fn grouped(&self) -> Vec<(Context, Action, Vec<KeyBinding>)> {
+        let mut grouped: Vec<(Context, Action, Vec<KeyBinding>)> = Vec::new();
+
+        for (context, key, action) in self.bindings() {
+            match grouped.last_mut() {
+                Some((last_context, last_action, keys))
+                    if *last_context == context && *last_action == action =>
+                {
+                    keys.push(key)
+                }
+                _ => grouped.push((context, action, vec![key])),
+            }
+        }
+
+        grouped
+    }
+
+    // `describe` is a plain-text table of every binding, as shown by
+    // `emojicp help keys`
+    pub fn describe(&self) -> String {
+        let mut text = String::new();
+        let mut current = None;
+
+        for (context, action, keys) in self.grouped() {
+            if current != Some(context) {
+                if current.is_some() {
+                    text.push('\n');
+                }
+                text.push_str(&format!("{}:\n", context.description()));
+                current = Some(context);
+            }
+
+            let keys: Vec<String> = keys.iter().map(KeyBinding::to_string).collect();
+            text.push_str(&format!(
+                "  {:<24} {:<22} {}\n",
+                keys.join(", "),
+                action.name(),
+                action.description()
+            ));
+        }
+
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    use crate::keymap::{
+        Action, Context, KeyBinding, KeyMap, KeyMapError, KeybindingsConfig, Preset,
+    };
+
+    fn key(binding: &str) -> KeyBinding {
+        binding.parse().unwrap()
+    }
+
+    #[test]
+    fn test_key_binding_parse() {
+        assert_eq!(
+            KeyBinding::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            key("ctrl-c")
+        );
+        assert_eq!(
+            KeyBinding::new(KeyCode::Enter, KeyModifiers::ALT),
+            key("Alt-Enter")
+        );
+        assert_eq!(
+            KeyBinding::new(KeyCode::F(1), KeyModifiers::NONE),
+            key("f1")
+        );
+        assert_eq!(
+            KeyBinding::new(KeyCode::Char('-'), KeyModifiers::CONTROL),
+            key("ctrl--")
+        );
+        assert_eq!(
+            KeyBinding::new(KeyCode::Char(' '), KeyModifiers::NONE),
+            key("space")
+        );
+
+        // shift is folded into characters
+        assert_eq!(
+            KeyBinding::new(KeyCode::Char('j'), KeyModifiers::NONE),
+            KeyBinding::new(KeyCode::Char('j'), KeyModifiers::SHIFT)
+        );
+
+        assert!("ctrl-".parse::<KeyBinding>().is_err());
+        assert!("hyper-x".parse::<KeyBinding>().is_err());
+        assert!("fx".parse::<KeyBinding>().is_err());
+
+        // key names are never split inside a character
+        assert_eq!(
+            Err(KeyMapError::UnknownKey(String::from("ab😀"))),
+            "ab😀".parse::<KeyBinding>()
+        );
+        assert_eq!(
+            KeyBinding::new(KeyCode::Char('é'), KeyModifiers::ALT),
+            key("alt-é")
+        );
+    }
+
+    #[test]
+    fn test_key_binding_display() {
+        for binding in ["ctrl-c", "alt-enter", "f12", "j", "space", "ctrl-alt-x"] {
+            assert_eq!(binding, key(binding).to_string());
+        }
+    }
+
+    #[test]
+    fn test_key_map_presets() {
+        let default = KeyMap::default();
+        assert_eq!(
+            Some(Action::Cancel),
+            default.action(Context::Input, key("ctrl-c"))
+        );
+        assert_eq!(
+            Some(Action::Unselect),
+            default.action(Context::List, key("left"))
+        );
+        assert_eq!(
+            Some(Action::BackwardChar),
+            default.action(Context::Input, key("left"))
+        );
+        assert_eq!(None, default.action(Context::List, key("j")));
+        assert_eq!(
+            Some(Action::ToggleMark),
+            default.action(Context::List, key("space"))
+        );
+        assert_eq!(None, default.action(Context::Input, key("space")));
+
+        let vim = KeyMap::new(&KeybindingsConfig {
+            preset: Preset::Vim,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            Some(Action::SelectNext),
+            vim.action(Context::List, key("j"))
+        );
+        assert_eq!(
+            Some(Action::SelectPrev),
+            vim.action(Context::Input, key("ctrl-p"))
+        );
+
+        // plain letters keep being typed into the search box
+        assert_eq!(None, vim.action(Context::Input, key("j")));
+
+        let emacs = KeyMap::new(&KeybindingsConfig {
+            preset: Preset::Emacs,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            Some(Action::Cancel),
+            emacs.action(Context::List, key("ctrl-g"))
+        );
+    }
+
+    #[test]
+    fn test_key_map_overrides() {
+        let mut config = KeybindingsConfig::default();
+        config.global.insert("ctrl-q".into(), "cancel".into());
+        config.global.insert("esc".into(), "none".into());
+        config.list.insert("x".into(), "toggle_favorite".into());
+
+        let keymap = KeyMap::new(&config).unwrap();
+        assert_eq!(
+            Some(Action::Cancel),
+            keymap.action(Context::Input, key("ctrl-q"))
+        );
+        assert_eq!(None, keymap.action(Context::Input, key("esc")));
+        assert_eq!(
+            Some(Action::ToggleFavorite),
+            keymap.action(Context::List, key("x"))
+        );
+        assert_eq!(None, keymap.action(Context::Input, key("x")));
+
+        // the keys of an action include global keys the context leaves alone
+        assert_eq!(
+            vec![key("ctrl-c"), key("ctrl-q")],
+            keymap.keys(Context::Input, Action::Cancel)
+        );
+        assert_eq!(
+            vec![key("ctrl-t"), key("x")],
+            keymap.keys(Context::List, Action::ToggleFavorite)
+        );
+        assert_eq!(
+            vec![key("left")],
+            keymap.keys(Context::Input, Action::BackwardChar)
+        );
+        assert!(keymap.keys(Context::List, Action::BackwardChar).is_empty());
+
+        config.list.insert("y".into(), "explode".into());
+        assert_eq!(
+            Some(KeyMapError::UnknownAction(String::from("explode"))),
+            KeyMap::new(&config).err()
+        );
+    }
+
+    #[test]
+    fn test_key_map_describe() {
+        let text = KeyMap::default().describe();
+        assert!(text.starts_with("Everywhere:\n"));
+        assert!(text.contains("\n  ctrl-c, esc"));
+        assert!(text.contains("While a suggestion is highlighted:\n  left"));
+
+        let cancel = text.lines().find(|line| line.contains("esc")).unwrap();
+        assert!(cancel.contains("cancel"));
+        assert!(cancel.ends_with("Exit without copying anything"));
+    }
+}
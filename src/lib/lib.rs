@@ -1,8 +1,41 @@
 pub mod carousel;
-pub mod clipboard;
 pub mod constants;
 pub mod emoji;
+pub mod error;
 pub mod pair;
+pub mod query;
 pub mod search;
-pub mod types;
+pub mod width;
+
+#[cfg(feature = "clipboard")]
+pub mod clipboard;
+
+#[cfg(feature = "tui")]
+pub mod config;
+#[cfg(feature = "tui")]
+pub mod favorites;
+#[cfg(feature = "tui")]
+pub mod grid;
+#[cfg(feature = "tui")]
+pub mod history;
+#[cfg(feature = "tui")]
+pub mod input;
+#[cfg(feature = "tui")]
+pub mod keymap;
+#[cfg(feature = "tui")]
+pub mod render;
+#[cfg(feature = "tui")]
+pub mod theme;
+#[cfg(feature = "tui")]
 pub mod ui;
+
+#[cfg(feature = "cli")]
+pub mod completions;
+#[cfg(feature = "cli")]
+pub mod manpage;
+#[cfg(feature = "cli")]
+pub mod shell;
+#[cfg(feature = "cli")]
+pub mod types;
+
+pub use error::Error;
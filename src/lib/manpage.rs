@@ -0,0 +1,168 @@
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use clap::CommandFactory;
+use clap_mangen::roff::{bold, roman, Roff};
+use clap_mangen::Man;
+
+use crate::config::{Config, CONFIG_KEYS, ENVIRONMENT};
+use crate::constants::*;
+use crate::keymap::KeyMap;
+use crate::types::Args;
+
+// The exit statuses `emojicp` can finish with, as listed in the manual page
+pub const EXIT_STATUSES: &[(u8, &str)] = &[
+    (0, "The emoji was copied or printed"),
+    (
+        EXIT_NOT_FOUND,
+        "No emoji has one of the given names or matches the search query",
+    ),
+    (
+        EXIT_USAGE,
+        "The command line, the search query or the regular expression is invalid",
+    ),
+    (
+        EXIT_CLIPBOARD,
+        "The emoji could not be copied to the clipboard",
+    ),
+    (
+        EXIT_CONFIG,
+        "The configuration file, an environment variable or the key bindings are invalid",
+    ),
+    (
+        EXIT_IO,
+        "Reading from or writing to the terminal failed, or another I/O error occurred",
+    ),
+    (EXIT_DATA, "The embedded emoji data is missing or corrupted"),
+    (EXIT_CANCELED, "The interactive picker was canceled"),
+];
+
+// `render` writes the emojicp(1) manual page in roff format to `out`. The
+// options and subcommands come from the clap definitions in `Args`, the
+// remaining sections from the tables the rest of the crate uses, so the
+// manual stays in sync with the program
+pub fn render(out: &mut dyn Write) -> io::Result<()> {
+    let man = Man::new(Args::command());
+
+    man.render_title(out)?;
+    man.render_name_section(out)?;
+    man.render_synopsis_section(out)?;
+    man.render_description_section(out)?;
+    man.render_options_section(out)?;
+    man.render_subcommands_section(out)?;
+
+    let mut roff = Roff::new();
+    configuration(&mut roff);
+    key_bindings(&mut roff);
+    table(&mut roff, "ENVIRONMENT", ENVIRONMENT);
+    files(&mut roff);
+    table(&mut roff, "EXIT STATUS", EXIT_STATUSES);
+    roff.to_writer(out)?;
+
+    man.render_version_section(out)?;
+    man.render_authors_section(out)
+}
+
+fn configuration(roff: &mut Roff) {
+    roff.control("SH", ["CONFIGURATION"]);
+    roff.text([roman(
+        "Settings are read from a TOML file. Environment variables override the \
+         file and command-line options override both. The keys are:",
+    )]);
+
+    for (key, description) in CONFIG_KEYS {
+        roff.control("TP", []);
+        roff.text([bold(*key)]);
+        roff.text([roman(*description)]);
+    }
+
+    roff.text([roman("The default configuration is:")]);
+    preformatted(roff, &Config::default().to_toml());
+}
+
+fn key_bindings(roff: &mut Roff) {
+    roff.control("SH", ["KEY BINDINGS"]);
+    roff.text([roman(
+        "The default key bindings of the interactive picker are listed below. \
+         Run `emojicp help keys` to list the bindings currently in effect.",
+    )]);
+    preformatted(roff, &KeyMap::default().describe());
+}
+
+fn files(roff: &mut Roff) {
+    table(
+        roff,
+        "FILES",
+        &[
+            (
+                "$XDG_CONFIG_HOME/emojicp/config.toml",
+                "The configuration file",
+            ),
+            (
+                "$XDG_DATA_HOME/emojicp/favorites",
+                "Emojis starred in the interactive picker",
+            ),
+            (
+                "$XDG_DATA_HOME/emojicp/history",
+                "Searches that ended with an emoji being copied",
+            ),
+        ],
+    );
+}
+
+fn table<T: Display>(roff: &mut Roff, heading: &str, rows: &[(T, &str)]) {
+    roff.control("SH", [heading]);
+
+    for (name, description) in rows {
+        roff.control("TP", []);
+        roff.text([bold(name.to_string())]);
+        roff.text([roman(*description)]);
+    }
+}
+
+fn preformatted(roff: &mut Roff, text: &str) {
+    roff.control("PP", []);
+    roff.control("nf", []);
+    for line in text.lines() {
+        roff.text([roman(line)]);
+    }
+    roff.control("fi", []);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{CONFIG_KEYS, ENVIRONMENT};
+    use crate::manpage::render;
+
+    #[test]
+    fn test_render() {
+        let mut page = Vec::new();
+        render(&mut page).unwrap();
+        let page = String::from_utf8(page).unwrap();
+
+        for section in [
+            "OPTIONS",
+            "SUBCOMMANDS",
+            "CONFIGURATION",
+            "KEY BINDINGS",
+            "ENVIRONMENT",
+            "FILES",
+            "EXIT STATUS",
+        ] {
+            let heading = if section.contains(' ') {
+                format!(".SH \"{}\"\n", section)
+            } else {
+                format!(".SH {}\n", section)
+            };
+            assert!(page.contains(&heading), "{} section", section);
+        }
+
+        // flags and subcommands come from the clap definitions
+        assert!(page.contains("\\-\\-separator"));
+        assert!(page.contains("shell\\-init"));
+
+        for (key, _) in CONFIG_KEYS.iter().chain(ENVIRONMENT) {
+            assert!(page.contains(key), "{} is documented", key);
+        }
+    }
+}
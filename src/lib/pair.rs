@@ -1,18 +1,144 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::constants::NAME_WIDTH;
+use crate::width;
+
+// The skin tones an emoji can be rendered in. `Default` is the yellow,
+// unmodified glyph; the rest map onto the five Fitzpatrick modifiers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum SkinTone {
+    #[default]
+    Default,
+    Light,
+    MediumLight,
+    Medium,
+    MediumDark,
+    Dark,
+}
+
+// The Emoji version an emoji was introduced in, e.g. `12.0`. Systems that
+// only know the emojis up to some version show newer ones as boxes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+// Versions are written as `12.0`, or just `12`
+impl FromStr for Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid Emoji version `{}`, expected e.g. `12.0`", s);
+        let (major, minor) = s.split_once('.').unwrap_or((s, "0"));
+
+        Ok(Version {
+            major: major.parse().map_err(|_| invalid())?,
+            minor: minor.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl TryFrom<String> for Version {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Version> for String {
+    fn from(version: Version) -> Self {
+        version.to_string()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct EmojiPair {
     pub description: String,
     pub emoji: String,
+
+    // The emoji rendered in each non-default skin tone, ordered from
+    // lightest to darkest. Empty if the emoji has no skin tone variants
+    pub skin_tones: Vec<String>,
+
+    // Other names the emoji can be found by, e.g. `satisfied` for `laughing`
+    pub aliases: Vec<String>,
+
+    // The Unicode group the emoji is listed under, e.g. `Animals & Nature`
+    pub category: String,
+
+    // The words of the emoji's Unicode name, e.g. `grinning`, `squinting`
+    // and `face` for `laughing`
+    pub keywords: Vec<String>,
+
+    // The Emoji version the emoji was introduced in
+    pub version: Version,
+}
+
+impl EmojiPair {
+    // `with_skin_tone` returns the emoji rendered in `tone`, falling back to
+    // the default glyph if the emoji doesn't come in different skin tones
+    pub fn with_skin_tone(&self, tone: SkinTone) -> &str {
+        let index = match tone {
+            SkinTone::Default => return &self.emoji,
+            SkinTone::Light => 0,
+            SkinTone::MediumLight => 1,
+            SkinTone::Medium => 2,
+            SkinTone::MediumDark => 3,
+            SkinTone::Dark => 4,
+        };
+
+        self.skin_tones
+            .get(index)
+            .map(String::as_str)
+            .unwrap_or(&self.emoji)
+    }
+
+    // `is_supported_by` tells whether systems that know the emojis up to
+    // `max_version` can show the emoji. Any system can without a maximum
+    pub fn is_supported_by(&self, max_version: Option<Version>) -> bool {
+        max_version.map_or(true, |max_version| self.version <= max_version)
+    }
+
+    // `codepoints` lists the Unicode code points the emoji is made of, e.g.
+    // `U+1F44B` for 👋
+    pub fn codepoints(&self) -> String {
+        codepoints(&self.emoji)
+    }
+}
+
+// `codepoints` lists the Unicode code points `glyph` is made of, e.g.
+// `U+1F44B U+1F3FD` for 👋🏽
+pub fn codepoints(glyph: &str) -> String {
+    glyph
+        .chars()
+        .map(|c| format!("U+{:04X}", c as u32))
+        .collect::<Vec<String>>()
+        .join(" ")
 }
 
+// Displays the name in a column of `NAME_WIDTH` terminal columns followed by
+// the emoji, so that the emojis of several pairs line up. A width given in
+// the format string, as in `{:30}`, sets the width of the column instead.
+// Names too long for it are cut short so that a space is always left before
+// the emoji
 impl fmt::Display for EmojiPair {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // Make sure that all the emojis are column aligned
-        let spaces = 20usize.saturating_sub(self.description.len());
-        let spaces = " ".repeat(spaces);
-        write!(f, "{}{}{}", self.description, spaces, self.emoji)
+        let column = f.width().unwrap_or(NAME_WIDTH).max(2);
+        let name = width::truncate(&self.description, column - 1);
+        write!(f, "{}{}", width::pad(&name, column), self.emoji)
     }
 }
 
@@ -24,13 +150,14 @@ impl AsRef<str> for EmojiPair {
 
 #[cfg(test)]
 mod tests {
-    use crate::pair::EmojiPair;
+    use crate::pair::{EmojiPair, SkinTone, Version};
 
     #[test]
     fn test_emoji_pair_display() {
         let mut pair = EmojiPair {
             description: String::from(""),
             emoji: String::from("🐵"),
+            ..Default::default()
         };
 
         // emojis with short descriptions are displayed properly
@@ -44,5 +171,82 @@ mod tests {
         // emojis with long descriptions are displayed properly
         pair.description = String::from("very cool monkey");
         assert_eq!("very cool monkey    🐵", format!("{}", pair));
+
+        // emojis with descriptions too long for the column are cut short
+        pair.description = String::from("the coolest monkey around");
+        assert_eq!("the coolest monkey… 🐵", format!("{}", pair));
+
+        // the width of the column can be set in the format string
+        assert_eq!("the coolest monkey around   🐵", format!("{:28}", pair));
+        assert_eq!("the… 🐵", format!("{:5}", pair));
+
+        // names are padded by the columns they take up, not their length
+        pair.description = String::from("café");
+        assert_eq!("café                🐵", format!("{}", pair));
+    }
+
+    #[test]
+    fn test_emoji_pair_with_skin_tone() {
+        let mut pair = EmojiPair {
+            description: String::from("wave"),
+            emoji: String::from("👋"),
+            ..Default::default()
+        };
+
+        // emojis without skin tone variants always use the default glyph
+        assert_eq!("👋", pair.with_skin_tone(SkinTone::Medium));
+
+        // emojis with skin tone variants use the requested tone
+        pair.skin_tones = ["👋🏻", "👋🏼", "👋🏽", "👋🏾", "👋🏿"]
+            .iter()
+            .map(|tone| tone.to_string())
+            .collect();
+        assert_eq!("👋", pair.with_skin_tone(SkinTone::Default));
+        assert_eq!("👋🏻", pair.with_skin_tone(SkinTone::Light));
+        assert_eq!("👋🏽", pair.with_skin_tone(SkinTone::Medium));
+        assert_eq!("👋🏿", pair.with_skin_tone(SkinTone::Dark));
+    }
+
+    #[test]
+    fn test_emoji_pair_codepoints() {
+        let mut pair = EmojiPair {
+            description: String::from("crab"),
+            emoji: String::from("🦀"),
+            ..Default::default()
+        };
+        assert_eq!("U+1F980", pair.codepoints());
+
+        // sequences list every code point, padded to at least four digits
+        pair.emoji = String::from("❤️");
+        assert_eq!("U+2764 U+FE0F", pair.codepoints());
+    }
+
+    #[test]
+    fn test_version() {
+        let version: Version = "12.1".parse().unwrap();
+        assert_eq!(
+            Version {
+                major: 12,
+                minor: 1
+            },
+            version
+        );
+        assert_eq!("12.1", version.to_string());
+        assert_eq!(Ok(Version { major: 5, minor: 0 }), "5".parse());
+        assert!("twelve".parse::<Version>().is_err());
+        assert!("12.x".parse::<Version>().is_err());
+
+        let pair = EmojiPair {
+            description: String::from("yawning_face"),
+            version: Version {
+                major: 12,
+                minor: 0,
+            },
+            ..Default::default()
+        };
+        assert!(pair.is_supported_by(None));
+        assert!(pair.is_supported_by(Some("12.0".parse().unwrap())));
+        assert!(pair.is_supported_by(Some("13".parse().unwrap())));
+        assert!(!pair.is_supported_by(Some("11.0".parse().unwrap())));
     }
 }
@@ -0,0 +1,537 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use bk_tree::BKTree;
+#[cfg(feature = "regex")]
+use regex::Regex;
+use thiserror::Error;
+
+use crate::pair::{EmojiPair, Version};
+
+// The filters written as `key:value`, for the error message listing them
+const KEYS: &str = "cat:, kw:, v: or tone:";
+
+// What is wrong with a search term that doesn't parse as a `Query`
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("missing closing quote in `{0}`")]
+    MissingQuote(String),
+
+    #[error("`!` has to be followed by what to leave out")]
+    EmptyNegation,
+
+    #[error("a quoted phrase can't be empty")]
+    EmptyPhrase,
+
+    #[error("`{0}` has to include part of a name")]
+    EmptyName(String),
+
+    #[error("`{0}:` has to be followed by a value")]
+    MissingValue(String),
+
+    #[error("`{0}` isn't an Emoji version such as 13.1")]
+    InvalidVersion(String),
+
+    #[error("`tone:` takes yes or no, not `{0}`")]
+    InvalidSkinTones(String),
+
+    #[error("unknown filter `{0}:`; use {}", KEYS)]
+    UnknownFilter(String),
+}
+
+// A parsed search term such as `cat:food !pizza ^b`. It is made of terms
+// separated by spaces, all of which have to match for an emoji to be
+// suggested
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    pub terms: Vec<Term>,
+}
+
+// One term of a query. A leading `!` negates it, so that it matches the
+// emojis its filter doesn't
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    pub negated: bool,
+    pub filter: Filter,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    // `smil`: the name, or one of the words it is made of, starts with this
+    Word(String),
+
+    // `"thumbs up"`: these words appear in this order in the name, an alias
+    // or the keywords, ignoring case
+    Phrase(String),
+
+    // `^cra`: the name starts with this
+    Prefix(String),
+
+    // `face$`: the name ends with this
+    Suffix(String),
+
+    // `^crab$`: the name is exactly this
+    Name(String),
+
+    // `cat:food`: the category contains this, ignoring case
+    Category(String),
+
+    // `kw:party`: one of the keywords starts with this, ignoring case
+    Keyword(String),
+
+    // `v:<=13`: the emoji was introduced in an Emoji version that compares
+    // to this one like so
+    Version(Comparison, Version),
+
+    // `tone:yes`: whether the emoji comes in different skin tones
+    SkinTones(bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+// The operators of `v:`, longest first so that `<=` isn't read as `<`
+const COMPARISONS: &[(&str, Comparison)] = &[
+    ("<=", Comparison::LessOrEqual),
+    (">=", Comparison::GreaterOrEqual),
+    ("<", Comparison::Less),
+    (">", Comparison::Greater),
+    ("=", Comparison::Equal),
+];
+
+impl Comparison {
+    // `holds` tells whether a value that is `ordering` to the one compared
+    // with satisfies the comparison
+    pub fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Less => ordering == Ordering::Less,
+            Comparison::LessOrEqual => ordering != Ordering::Greater,
+            Comparison::Equal => ordering == Ordering::Equal,
+            Comparison::GreaterOrEqual => ordering != Ordering::Less,
+            Comparison::Greater => ordering == Ordering::Greater,
+        }
+    }
+}
+
+impl Query {
+    pub fn matches(&self, pair: &EmojiPair) -> bool {
+        self.terms.iter().all(|term| term.matches(pair))
+    }
+
+    // `word` is the part of a name that suggestions are ranked by: the
+    // first word, prefix or name the query doesn't leave out
+    pub fn word(&self) -> Option<&str> {
+        self.terms
+            .iter()
+            .filter(|term| !term.negated)
+            .find_map(|term| match &term.filter {
+                Filter::Word(word) | Filter::Prefix(word) | Filter::Name(word) => {
+                    Some(word.as_str())
+                }
+                _ => None,
+            })
+    }
+
+    // `find` returns the emojis in `tree` that match the query, closest to
+    // its word first: those with the fewest characters left in their name
+    // after the word. Names further than `tolerance` from the word are left
+    // out; without a word, the shortest names come first
+    pub fn find<'a>(&self, tree: &'a BKTree<EmojiPair>, tolerance: u32) -> Vec<&'a EmojiPair> {
+        let word = self.word();
+
+        let mut results: Vec<(u32, &EmojiPair)> = every(tree)
+            .into_iter()
+            .filter(|(_, pair)| self.matches(pair))
+            .filter_map(|(length, pair)| match word {
+                Some(word) => distance(word, &pair.description)
+                    .filter(|distance| *distance <= tolerance)
+                    .map(|distance| (distance, pair)),
+                None => Some((length, pair)),
+            })
+            .collect();
+        results.sort_by(|(a, a_pair), (b, b_pair)| {
+            a.cmp(b)
+                .then_with(|| a_pair.description.cmp(&b_pair.description))
+        });
+
+        results.into_iter().map(|(_, pair)| pair).collect()
+    }
+}
+
+// `every` lists every emoji in `tree` along with the length of its name,
+// which is how far it is from an empty name. The tree adds to the
+// tolerance, so it has to leave room for that
+fn every(tree: &BKTree<EmojiPair>) -> Vec<(u32, &EmojiPair)> {
+    tree.find(&EmojiPair::default(), u32::MAX / 2).collect()
+}
+
+// `regex_message` explains in a single line what is wrong with a regular
+// expression, short enough to be shown beneath the search box
+#[cfg(feature = "regex")]
+pub fn regex_message(error: &regex::Error) -> String {
+    // Syntax errors point at the problem over several lines, ending with
+    // what it is
+    let message = error.to_string();
+    let last = message.lines().last().unwrap_or_default();
+    last.strip_prefix("error: ").unwrap_or(last).to_owned()
+}
+
+// `find_regex` returns the emojis in `tree` with a name or alias `regex`
+// matches, in alphabetical order. Every emoji is checked, rather than the
+// closest ones to a word
+#[cfg(feature = "regex")]
+pub fn find_regex<'a>(regex: &Regex, tree: &'a BKTree<EmojiPair>) -> Vec<&'a EmojiPair> {
+    let mut results: Vec<&EmojiPair> = every(tree)
+        .into_iter()
+        .map(|(_, pair)| pair)
+        .filter(|pair| {
+            regex.is_match(&pair.description)
+                || pair.aliases.iter().any(|alias| regex.is_match(alias))
+        })
+        .collect();
+    results.sort_by(|a, b| a.description.cmp(&b.description));
+    results
+}
+
+// `distance` is the edit distance between `word` and the part of `name`
+// starting where the word matched it, which is how many characters follow
+// the word. `None` if the name doesn't contain the word at the start of one
+// of its words
+fn distance(word: &str, name: &str) -> Option<u32> {
+    let start = if name.starts_with(word) {
+        0
+    } else {
+        name.find(&format!("_{}", word))? + 1
+    };
+
+    let rest = name[start..].chars().count() - word.chars().count();
+    Some(rest as u32)
+}
+
+impl Term {
+    pub fn matches(&self, pair: &EmojiPair) -> bool {
+        self.filter.matches(pair) != self.negated
+    }
+}
+
+impl Filter {
+    pub fn matches(&self, pair: &EmojiPair) -> bool {
+        let name = &pair.description;
+        match self {
+            Filter::Word(word) => {
+                name.starts_with(word.as_str()) || name.contains(&format!("_{}", word))
+            }
+            Filter::Phrase(phrase) => {
+                let joined = phrase.replace(' ', "_");
+                name.to_lowercase().contains(&joined)
+                    || pair
+                        .aliases
+                        .iter()
+                        .any(|alias| alias.to_lowercase().contains(&joined))
+                    || pair
+                        .keywords
+                        .join(" ")
+                        .to_lowercase()
+                        .contains(phrase.as_str())
+            }
+            Filter::Prefix(prefix) => name.starts_with(prefix.as_str()),
+            Filter::Suffix(suffix) => name.ends_with(suffix.as_str()),
+            Filter::Name(exact) => name == exact,
+            Filter::Category(category) => pair.category.to_lowercase().contains(category.as_str()),
+            Filter::Keyword(keyword) => pair
+                .keywords
+                .iter()
+                .any(|known| known.to_lowercase().starts_with(keyword.as_str())),
+            Filter::Version(comparison, version) => comparison.holds(pair.version.cmp(version)),
+            Filter::SkinTones(has) => pair.skin_tones.is_empty() != *has,
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut terms = Vec::new();
+        let mut rest = text.trim_start();
+
+        while !rest.is_empty() {
+            let (token, remainder) = next_token(rest)?;
+            terms.push(token.parse()?);
+            rest = remainder.trim_start();
+        }
+
+        Ok(Query { terms })
+    }
+}
+
+// `next_token` splits the first term off `text`: everything up to the next
+// space, or up to the closing quote of a phrase
+fn next_token(text: &str) -> Result<(&str, &str), ParseError> {
+    let start = if text.starts_with("!\"") { 2 } else { 1 };
+    if text[start - 1..].starts_with('"') {
+        let Some(length) = text[start..].find('"') else {
+            return Err(ParseError::MissingQuote(text.to_owned()));
+        };
+        return Ok(text.split_at(start + length + 1));
+    }
+
+    Ok(text.split_at(text.find(char::is_whitespace).unwrap_or(text.len())))
+}
+
+impl FromStr for Term {
+    type Err = ParseError;
+
+    fn from_str(token: &str) -> Result<Self, Self::Err> {
+        let (negated, body) = match token.strip_prefix('!') {
+            Some(body) => (true, body),
+            None => (false, token),
+        };
+        if body.is_empty() {
+            return Err(ParseError::EmptyNegation);
+        }
+
+        let filter = if let Some(phrase) = body.strip_prefix('"') {
+            let phrase = phrase.strip_suffix('"').unwrap_or(phrase).trim();
+            if phrase.is_empty() {
+                return Err(ParseError::EmptyPhrase);
+            }
+            Filter::Phrase(phrase.to_lowercase())
+        } else if let Some((key, value)) = body.split_once(':') {
+            keyed(key, value)?
+        } else {
+            anchored(body)?
+        };
+
+        Ok(Term { negated, filter })
+    }
+}
+
+// `anchored` reads a part of a name, with `^` tying it to the start of the
+// name and `$` to the end
+fn anchored(body: &str) -> Result<Filter, ParseError> {
+    let (start, rest) = match body.strip_prefix('^') {
+        Some(rest) => (true, rest),
+        None => (false, body),
+    };
+    let (end, text) = match rest.strip_suffix('$') {
+        Some(text) => (true, text),
+        None => (false, rest),
+    };
+
+    if text.is_empty() {
+        return Err(ParseError::EmptyName(body.to_owned()));
+    }
+
+    let text = text.to_owned();
+    Ok(match (start, end) {
+        (true, true) => Filter::Name(text),
+        (true, false) => Filter::Prefix(text),
+        (false, true) => Filter::Suffix(text),
+        (false, false) => Filter::Word(text),
+    })
+}
+
+fn keyed(key: &str, value: &str) -> Result<Filter, ParseError> {
+    let filter = match key {
+        "cat" | "category" | "kw" | "keyword" | "v" | "version" | "tone" if value.is_empty() => {
+            return Err(ParseError::MissingValue(key.to_owned()));
+        }
+        "cat" | "category" => Filter::Category(value.to_lowercase()),
+        "kw" | "keyword" => Filter::Keyword(value.to_lowercase()),
+        "v" | "version" => {
+            let (comparison, number) = COMPARISONS
+                .iter()
+                .find_map(|(operator, comparison)| {
+                    value
+                        .strip_prefix(operator)
+                        .map(|number| (*comparison, number))
+                })
+                .unwrap_or((Comparison::Equal, value));
+            let version = number
+                .parse()
+                .map_err(|_| ParseError::InvalidVersion(number.to_owned()))?;
+            Filter::Version(comparison, version)
+        }
+        "tone" => match value {
+            "yes" | "y" | "true" => Filter::SkinTones(true),
+            "no" | "n" | "false" => Filter::SkinTones(false),
+            _ => return Err(ParseError::InvalidSkinTones(value.to_owned())),
+        },
+        _ => return Err(ParseError::UnknownFilter(key.to_owned())),
+    };
+
+    Ok(filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use bk_tree::{metrics, BKTree};
+
+    use crate::pair::EmojiPair;
+    #[cfg(feature = "regex")]
+    #[cfg(feature = "regex")]
+    use regex::Regex;
+
+    #[cfg(feature = "regex")]
+    use crate::query::{find_regex, regex_message};
+    use crate::query::{Comparison, Filter, ParseError, Query, Term};
+
+    fn pair(description: &str, category: &str, keywords: &[&str], version: &str) -> EmojiPair {
+        EmojiPair {
+            description: String::from(description),
+            category: String::from(category),
+            keywords: keywords.iter().map(|word| String::from(*word)).collect(),
+            version: version.parse().unwrap(),
+            ..Default::default()
+        }
+    }
+
+    fn tree() -> BKTree<EmojiPair> {
+        let mut tree = BKTree::new(metrics::Levenshtein);
+        for pair in [
+            pair("crab", "Animals & Nature", &["crab"], "1.0"),
+            pair("crayon", "Objects", &["crayon"], "0.7"),
+            pair("pizza", "Food & Drink", &["pizza"], "0.6"),
+            pair(
+                "partying_face",
+                "Smileys & Emotion",
+                &["partying", "face"],
+                "11.0",
+            ),
+            pair(
+                "smiling_face_with_tear",
+                "Smileys & Emotion",
+                &["smiling", "face", "with", "tear"],
+                "13.0",
+            ),
+            EmojiPair {
+                skin_tones: vec![String::from("👋🏻")],
+                ..pair("wave", "People & Body", &["waving", "hand"], "0.6")
+            },
+        ] {
+            tree.add(pair);
+        }
+        tree
+    }
+
+    fn find(query: &str) -> Vec<String> {
+        let query: Query = query.parse().unwrap();
+        query
+            .find(&tree(), 10)
+            .into_iter()
+            .map(|pair| pair.description.clone())
+            .collect()
+    }
+
+    #[cfg(feature = "regex")]
+    fn find_pattern(pattern: &str) -> Vec<String> {
+        find_regex(&Regex::new(pattern).unwrap(), &tree())
+            .into_iter()
+            .map(|pair| pair.description.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let query: Query = "cra !cat:food \"waving hand\" ^b face$ ^crab$ v:<=13 tone:no"
+            .parse()
+            .unwrap();
+
+        let filters: Vec<(bool, Filter)> = query
+            .terms
+            .into_iter()
+            .map(|Term { negated, filter }| (negated, filter))
+            .collect();
+        assert_eq!(
+            vec![
+                (false, Filter::Word(String::from("cra"))),
+                (true, Filter::Category(String::from("food"))),
+                (false, Filter::Phrase(String::from("waving hand"))),
+                (false, Filter::Prefix(String::from("b"))),
+                (false, Filter::Suffix(String::from("face"))),
+                (false, Filter::Name(String::from("crab"))),
+                (
+                    false,
+                    Filter::Version(Comparison::LessOrEqual, "13".parse().unwrap())
+                ),
+                (false, Filter::SkinTones(false)),
+            ],
+            filters
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (query, message) in [
+            ("\"waving hand", "missing closing quote in `\"waving hand`"),
+            ("crab !", "`!` has to be followed by what to leave out"),
+            ("\"\"", "a quoted phrase can't be empty"),
+            ("^$", "`^$` has to include part of a name"),
+            ("cat:", "`cat:` has to be followed by a value"),
+            ("v:<=new", "`new` isn't an Emoji version such as 13.1"),
+            ("tone:maybe", "`tone:` takes yes or no, not `maybe`"),
+            (
+                "colour:red",
+                "unknown filter `colour:`; use cat:, kw:, v: or tone:",
+            ),
+        ] {
+            assert_eq!(message, query.parse::<Query>().unwrap_err().to_string());
+        }
+
+        assert_eq!(
+            Err(ParseError::MissingValue(String::from("cat"))),
+            "cat:".parse::<Query>()
+        );
+    }
+
+    #[test]
+    fn test_find() {
+        // a word matches the start of the name or of one of its words
+        assert_eq!(vec!["crab", "crayon"], find("cra"));
+        assert_eq!(vec!["partying_face"], find("face !smil"));
+        assert_eq!(vec!["smiling_face_with_tear"], find("tear"));
+
+        assert_eq!(vec!["crab"], find("^crab$"));
+        assert_eq!(vec!["partying_face"], find("face$ kw:part"));
+        assert_eq!(vec!["wave"], find("\"Waving Hand\""));
+        assert_eq!(vec!["wave"], find("tone:yes"));
+        assert_eq!(vec!["pizza"], find("cat:food"));
+
+        // without a word every emoji is considered, shortest names first
+        assert_eq!(vec!["crab", "wave", "pizza", "crayon"], find("v:<11"));
+        assert_eq!(vec!["smiling_face_with_tear"], find("v:>=13.0"));
+        assert_eq!(vec!["crayon"], find("v:0.7"));
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn test_regex() {
+        assert_eq!(vec!["crab", "crayon"], find_pattern("^cra"));
+        assert_eq!(
+            vec!["partying_face", "smiling_face_with_tear"],
+            find_pattern("_face(_|$)")
+        );
+
+        // aliases are matched too
+        let mut tree = tree();
+        tree.add(EmojiPair {
+            description: String::from("laughing"),
+            aliases: vec![String::from("satisfied")],
+            ..Default::default()
+        });
+        let found = find_regex(&Regex::new("^sat").unwrap(), &tree);
+        assert_eq!("laughing", found[0].description);
+
+        // the pattern is built at run time so that clippy doesn't reject it
+        let pattern = String::from("cra(b");
+        let error = Regex::new(&pattern).unwrap_err();
+        assert_eq!("unclosed group", regex_message(&error));
+    }
+}
@@ -0,0 +1,180 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use crossterm::{cursor, execute, style::Print};
+
+use crate::error::Error;
+use crate::pair::EmojiPair;
+
+// `Probe` draws a glyph at `x, y` and measures how many columns the terminal
+// moved the cursor by, which is how wide the terminal drew it
+pub trait Probe {
+    fn measure(&mut self, glyph: &str, x: u16, y: u16) -> Result<u16, Error>;
+}
+
+// `TerminalProbe` asks the terminal where the cursor ended up after drawing
+// the glyph, on the controlling terminal that the picker is drawn on
+pub struct TerminalProbe;
+
+impl Probe for TerminalProbe {
+    fn measure(&mut self, glyph: &str, x: u16, y: u16) -> Result<u16, Error> {
+        let mut stdout = io::stdout();
+        execute!(stdout, cursor::MoveTo(x, y), Print(glyph)).map_err(Error::Terminal)?;
+        stdout.flush().map_err(Error::Terminal)?;
+
+        let (column, row) = cursor::position().map_err(Error::Terminal)?;
+
+        // A glyph drawn as several that didn't fit on the line wrapped onto
+        // the next one
+        if row != y {
+            return Ok(u16::MAX);
+        }
+
+        Ok(column.saturating_sub(x))
+    }
+}
+
+// `RenderCheck` remembers which emoji sequences the terminal draws as a
+// single emoji. Sequences it hasn't checked yet are assumed to be drawn
+// properly
+#[derive(Debug, Default)]
+pub struct RenderCheck {
+    enabled: bool,
+    renders: HashMap<String, bool>,
+}
+
+impl RenderCheck {
+    pub fn new(enabled: bool) -> RenderCheck {
+        RenderCheck {
+            enabled,
+            renders: HashMap::new(),
+        }
+    }
+
+    // `unchecked` picks the emoji sequences out of `glyphs` that haven't
+    // been checked yet, without duplicates
+    pub fn unchecked<'a>(&self, glyphs: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+        if !self.enabled {
+            return Vec::new();
+        }
+
+        let mut unchecked: Vec<&str> = Vec::new();
+        for glyph in glyphs {
+            if is_sequence(glyph)
+                && !self.renders.contains_key(glyph)
+                && !unchecked.contains(&glyph)
+            {
+                unchecked.push(glyph);
+            }
+        }
+
+        unchecked
+    }
+
+    // `check` measures `glyph` with `probe` at `x, y`. If the terminal can't
+    // be probed, e.g. because it doesn't answer, checking is turned off
+    // rather than failing the picker
+    pub fn check(&mut self, probe: &mut impl Probe, glyph: &str, x: u16, y: u16) {
+        match probe.measure(glyph, x, y) {
+            Ok(width) => {
+                self.renders.insert(glyph.to_owned(), width == 2);
+            }
+            Err(_) => self.enabled = false,
+        }
+    }
+
+    pub fn renders(&self, glyph: &str) -> bool {
+        self.renders.get(glyph).copied().unwrap_or(true)
+    }
+
+    // `shown` is `pair` as it can be drawn: if the terminal doesn't draw its
+    // emoji as a single one, the code points of the emoji are shown instead
+    pub fn shown<'a>(&self, pair: &'a EmojiPair) -> Cow<'a, EmojiPair> {
+        if self.renders(&pair.emoji) {
+            Cow::Borrowed(pair)
+        } else {
+            Cow::Owned(EmojiPair {
+                emoji: pair.codepoints(),
+                ..pair.clone()
+            })
+        }
+    }
+}
+
+// `is_sequence` tells whether `glyph` is made of several code points, not
+// counting variation selectors: joined emojis such as 👩‍🦰, skin tones, flags
+// and keycaps. These are the emojis terminals are likely to draw as pieces
+pub fn is_sequence(glyph: &str) -> bool {
+    glyph
+        .chars()
+        .filter(|c| !matches!(c, '\u{FE0E}' | '\u{FE0F}'))
+        .count()
+        > 1
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::error::Error;
+    use crate::pair::EmojiPair;
+    use crate::render::{is_sequence, Probe, RenderCheck};
+
+    // `Widths` draws the glyphs it knows at the given width and fails for
+    // the rest, like a terminal that doesn't answer
+    struct Widths(HashMap<&'static str, u16>);
+
+    impl Probe for Widths {
+        fn measure(&mut self, glyph: &str, _: u16, _: u16) -> Result<u16, Error> {
+            self.0
+                .get(glyph)
+                .copied()
+                .ok_or(Error::Terminal(std::io::ErrorKind::TimedOut.into()))
+        }
+    }
+
+    #[test]
+    fn test_is_sequence() {
+        assert!(!is_sequence("🦀"));
+        assert!(!is_sequence("❤️"));
+        assert!(is_sequence("👩‍🦰"));
+        assert!(is_sequence("👋🏽"));
+        assert!(is_sequence("🇫🇷"));
+    }
+
+    #[test]
+    fn test_render_check() {
+        let mut probe = Widths(HashMap::from([("👩‍🦰", 4), ("👋🏽", 2)]));
+        let mut check = RenderCheck::new(true);
+
+        // only sequences are checked, once each
+        assert_eq!(vec!["👩‍🦰", "👋🏽"], check.unchecked(["🦀", "👩‍🦰", "👋🏽", "👩‍🦰"]));
+        check.check(&mut probe, "👩‍🦰", 0, 0);
+        check.check(&mut probe, "👋🏽", 0, 0);
+        assert!(check.unchecked(["👩‍🦰", "👋🏽"]).is_empty());
+
+        assert!(!check.renders("👩‍🦰"));
+        assert!(check.renders("👋🏽"));
+        assert!(check.renders("🦀"));
+
+        // emojis the terminal can't draw are shown as code points
+        let pair = EmojiPair {
+            description: String::from("red_haired_woman"),
+            emoji: String::from("👩‍🦰"),
+            ..Default::default()
+        };
+        assert_eq!("U+1F469 U+200D U+1F9B0", check.shown(&pair).emoji);
+
+        // a terminal that doesn't answer turns checking off
+        check.check(&mut probe, "🇫🇷", 0, 0);
+        assert!(check.unchecked(["🇫🇷"]).is_empty());
+        assert!(check.renders("🇫🇷"));
+    }
+
+    #[test]
+    fn test_render_check_disabled() {
+        let check = RenderCheck::new(false);
+        assert!(check.unchecked(["👩‍🦰"]).is_empty());
+    }
+}
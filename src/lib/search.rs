@@ -1,87 +1,264 @@
-use std::error::Error;
-use std::io::stdin;
+#[cfg(feature = "tui")]
+use std::io;
+#[cfg(feature = "tui")]
+use std::time::Duration;
 
 use bk_tree::BKTree;
-use clipboard_anywhere::set_clipboard;
-use termion::event::Key;
-use termion::input::TermRead;
-use termion::raw::IntoRawMode;
-
-use crate::constants::*;
-use crate::emoji::Emoji;
-use crate::pair::EmojiPair;
-use crate::ui::{run_app, App};
-use crate::{carousel, types::*};
-
-use std::{
-    io,
-    time::{Duration, Instant},
-};
-
-use crate::clipboard;
-use crate::pair::*;
-use crate::types::*;
+#[cfg(feature = "tui")]
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    event::{
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+#[cfg(feature = "tui")]
 use ratatui::backend::CrosstermBackend;
-use ratatui::widgets::*;
-use ratatui::{
-    backend::{self, Backend},
-    buffer::{self, Buffer},
-    layout::{self, Alignment, Constraint, Corner, Direction, Layout, Margin, Rect},
-    style::{self, Color, Modifier, Style, *},
-    symbols::{self, Marker},
-    terminal::{self, Frame, Terminal, TerminalOptions, Viewport},
-    text::{self, Line, Masked, Span, Text},
-};
+#[cfg(feature = "tui")]
+use ratatui::{Terminal, TerminalOptions, Viewport};
+#[cfg(feature = "regex")]
+use regex::Regex;
 
-pub fn search_exact(description: String) -> Result<EmojiPair, Box<dyn Error>> {
-    // Get the raw bytes from the embedded file
-    let emoji_file = Emoji::get(EMOJI_TREE_FILE).ok_or(Box::new(EmojiError::IoError {
-        filename: String::from(EMOJI_TREE_FILE),
-    }))?;
-    let encoded_tree = emoji_file.data.as_ref();
-
-    // Decode the BKTree
-    let tree: BKTree<EmojiPair> = bincode::deserialize(encoded_tree).map_err(|_| {
-        Box::new(EmojiError::CannotDeserializeBKTree {
-            filename: String::from(EMOJI_TREE_FILE),
-        })
-    })?;
+#[cfg(feature = "tui")]
+use crate::config::Config;
+use crate::constants::*;
+use crate::emoji::Emoji;
+use crate::error::Error;
+use crate::pair::{EmojiPair, Version};
+#[cfg(feature = "regex")]
+use crate::query::find_regex;
+use crate::query::Query;
+#[cfg(feature = "tui")]
+use crate::render::TerminalProbe;
+#[cfg(feature = "tui")]
+use crate::ui::{run_app, App, TerminalEvents};
 
+pub fn search_exact(description: String) -> Result<EmojiPair, Error> {
     // Search the BKTree for the emoji
-    Ok(tree
+    emoji_tree()?
         .find_exact(&EmojiPair {
             description: description.clone(),
-            emoji: String::from(""), // doesn't matter for the search
+            ..Default::default() // the emoji doesn't matter for the search
         })
-        .ok_or(Box::new(EmojiError::InvalidEmojiName { description }))
-        .cloned()?)
+        .cloned()
+        .ok_or(Error::InvalidEmojiName { description })
+}
+
+// `search_supported` is `search_exact` for systems that can only show the
+// emojis up to `max_version`. Newer emojis are reported rather than returned
+pub fn search_supported(
+    description: String,
+    max_version: Option<Version>,
+) -> Result<EmojiPair, Error> {
+    let pair = search_exact(description)?;
+    match max_version {
+        Some(max_version) if !pair.is_supported_by(Some(max_version)) => {
+            Err(Error::UnsupportedEmoji {
+                description: pair.description,
+                version: pair.version,
+                max_version,
+            })
+        }
+        _ => Ok(pair),
+    }
+}
+
+// `search_query` returns every emoji matching `query`, closest matches
+// first, leaving out those newer than `max_version`
+pub fn search_query(
+    query: &str,
+    tolerance: u32,
+    max_version: Option<Version>,
+) -> Result<Vec<EmojiPair>, Error> {
+    let parsed: Query = query
+        .parse()
+        .map_err(|source| Error::InvalidQuery { source })?;
+
+    let tree = emoji_tree()?;
+    let pairs: Vec<EmojiPair> = parsed
+        .find(&tree, tolerance)
+        .into_iter()
+        .filter(|pair| pair.is_supported_by(max_version))
+        .cloned()
+        .collect();
+
+    if pairs.is_empty() {
+        return Err(Error::NoMatches {
+            query: query.to_owned(),
+        });
+    }
+
+    Ok(pairs)
+}
+
+// `search_regex` returns every emoji with a name or alias matching the
+// regular expression `pattern`, in alphabetical order, leaving out those
+// newer than `max_version`
+#[cfg(feature = "regex")]
+pub fn search_regex(pattern: &str, max_version: Option<Version>) -> Result<Vec<EmojiPair>, Error> {
+    let regex = Regex::new(pattern).map_err(|source| Error::InvalidRegex { source })?;
+
+    let tree = emoji_tree()?;
+    let pairs: Vec<EmojiPair> = find_regex(&regex, &tree)
+        .into_iter()
+        .filter(|pair| pair.is_supported_by(max_version))
+        .cloned()
+        .collect();
+
+    if pairs.is_empty() {
+        return Err(Error::NoMatches {
+            query: pattern.to_owned(),
+        });
+    }
+
+    Ok(pairs)
+}
+
+// `emoji_tree` decodes the BKTree of every emoji embedded in the executable
+pub fn emoji_tree() -> Result<BKTree<EmojiPair>, Error> {
+    decode(EMOJI_TREE_FILE)
+}
+
+// `emoji_list` returns every emoji in the embedded data, in the order they
+// appear in `RAW_PAIRS`
+pub fn emoji_list() -> Result<Vec<EmojiPair>, Error> {
+    decode(EMOJI_LIST_FILE)
+}
+
+fn decode<T: serde::de::DeserializeOwned>(filename: &str) -> Result<T, Error> {
+    // Get the raw bytes from the embedded file
+    let file = Emoji::get(filename).ok_or_else(|| Error::MissingEmbeddedFile {
+        filename: String::from(filename),
+    })?;
+
+    bincode::deserialize(file.data.as_ref()).map_err(|source| Error::CannotDecodeEmbeddedFile {
+        filename: String::from(filename),
+        source,
+    })
 }
 
-pub fn search_interactive() -> Result<EmojiPair, Box<dyn Error>> {
-    // Initialize terminal for interactive environment
+#[cfg(feature = "tui")]
+pub fn search_interactive(config: &Config) -> Result<Vec<EmojiPair>, Error> {
+    // Problems with the configuration or the emoji data are reported before
+    // the terminal is touched
+    let app = App::new(config)?;
+
+    // When the emoji is being captured, e.g. by the widgets from
+    // `emojicp shell-init`, the picker is drawn on the terminal instead
+    let _stdout = StdoutToTerminal::redirect().map_err(Error::Terminal)?;
+
+    let viewport = match config.height {
+        Some(height) => Viewport::Inline(height.max(MIN_INLINE_HEIGHT)),
+        None => Viewport::Fullscreen,
+    };
+    let mut terminal = enter(viewport.clone()).map_err(Error::Terminal)?;
+
+    let tick_rate = Duration::from_millis(config.tick_rate_ms);
+    let res = run_app(
+        &mut terminal,
+        app,
+        &mut TerminalEvents,
+        &mut TerminalProbe,
+        tick_rate,
+    );
+
+    leave(&mut terminal, viewport).map_err(Error::Terminal)?;
+
+    res
+}
+
+// `enter` initializes the terminal for the interactive picker. An inline
+// viewport is drawn beneath the prompt rather than on the alternate screen
+#[cfg(feature = "tui")]
+fn enter(viewport: Viewport) -> io::Result<Terminal<CrosstermBackend<io::Stdout>>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    if viewport == Viewport::Fullscreen {
+        execute!(stdout, EnterAlternateScreen)?;
+    }
+    execute!(
+        stdout,
+        EnableMouseCapture,
+        EnableBracketedPaste,
+        EnableFocusChange
+    )?;
 
-    // Create app and run it
-    let tick_rate = Duration::from_millis(250);
-    let app = App::new();
-    let res = run_app(&mut terminal, app, tick_rate);
+    Terminal::with_options(CrosstermBackend::new(stdout), TerminalOptions { viewport })
+}
 
-    // Restore terminal to normal mode
+// `leave` restores the terminal to normal mode
+#[cfg(feature = "tui")]
+fn leave(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    viewport: Viewport,
+) -> io::Result<()> {
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        DisableFocusChange
     )?;
+    match viewport {
+        // Erase the picker so the prompt continues where it was drawn
+        Viewport::Inline(_) => terminal.clear()?,
+        _ => execute!(terminal.backend_mut(), LeaveAlternateScreen)?,
+    }
+    terminal.show_cursor()
+}
 
-    res
+// `StdoutToTerminal` points stdout at the controlling terminal for as long
+// as it is alive if stdout is redirected, restoring it when dropped so the
+// selected emoji is still printed to the original stdout
+#[cfg(all(feature = "tui", unix))]
+struct StdoutToTerminal {
+    original: Option<std::os::fd::OwnedFd>,
+}
+
+#[cfg(all(feature = "tui", unix))]
+impl StdoutToTerminal {
+    fn redirect() -> io::Result<StdoutToTerminal> {
+        use std::fs::OpenOptions;
+        use std::io::{IsTerminal, Write};
+        use std::os::fd::AsFd;
+
+        let stdout = io::stdout();
+        if stdout.is_terminal() {
+            return Ok(StdoutToTerminal { original: None });
+        }
+
+        let terminal = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+        let original = stdout.as_fd().try_clone_to_owned()?;
+        stdout.lock().flush()?;
+        rustix::stdio::dup2_stdout(&terminal)?;
+
+        Ok(StdoutToTerminal {
+            original: Some(original),
+        })
+    }
+}
+
+#[cfg(all(feature = "tui", unix))]
+impl Drop for StdoutToTerminal {
+    fn drop(&mut self) {
+        use std::io::Write;
+
+        if let Some(original) = &self.original {
+            let _ = io::stdout().flush();
+            let _ = rustix::stdio::dup2_stdout(original);
+        }
+    }
+}
+
+// Other platforms have no `/dev/tty` to draw on, so the picker always
+// uses stdout
+#[cfg(all(feature = "tui", not(unix)))]
+struct StdoutToTerminal;
+
+#[cfg(all(feature = "tui", not(unix)))]
+impl StdoutToTerminal {
+    fn redirect() -> io::Result<StdoutToTerminal> {
+        Ok(StdoutToTerminal)
+    }
 }
@@ -0,0 +1,39 @@
+use clap::ValueEnum;
+
+// The shells `emojicp shell-init` can integrate with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    // `init_script` is the shell code defining a line-editor widget that
+    // runs the picker in print mode and inserts the emoji at the cursor
+    pub fn init_script(self) -> &'static str {
+        match self {
+            Shell::Bash => include_str!("shell/init.bash"),
+            Shell::Zsh => include_str!("shell/init.zsh"),
+            Shell::Fish => include_str!("shell/init.fish"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::ValueEnum;
+
+    use crate::shell::Shell;
+
+    #[test]
+    fn test_init_script() {
+        for shell in Shell::value_variants() {
+            let script = shell.init_script();
+
+            // the picker has to print the emoji so the widget can insert it
+            assert!(script.contains("emojicp --output stdout"));
+            assert!(script.contains("\\e:"));
+        }
+    }
+}
@@ -0,0 +1,16 @@
+# emojicp shell integration for bash
+#
+# Add `eval "$(emojicp shell-init bash)"` to ~/.bashrc, then press Alt-: to
+# pick an emoji and insert it at the cursor.
+
+__emojicp_widget() {
+  local emoji
+  emoji="$(emojicp --output stdout < /dev/tty)"
+  if [[ -n "$emoji" ]]; then
+    READLINE_LINE="${READLINE_LINE:0:READLINE_POINT}${emoji}${READLINE_LINE:READLINE_POINT}"
+    READLINE_POINT=$((READLINE_POINT + ${#emoji}))
+  fi
+}
+
+bind -m emacs-standard -x '"\e:": __emojicp_widget'
+bind -m vi-insert -x '"\e:": __emojicp_widget'
@@ -0,0 +1,16 @@
+# emojicp shell integration for fish
+#
+# Add `emojicp shell-init fish | source` to ~/.config/fish/config.fish, then
+# press Alt-: to pick an emoji and insert it at the cursor.
+
+function emojicp-widget -d "Pick an emoji and insert it at the cursor"
+    set -l emoji (emojicp --output stdout </dev/tty)
+    and test -n "$emoji"
+    and commandline --insert -- $emoji
+    commandline --function repaint
+end
+
+bind \e: emojicp-widget
+if bind --mode insert >/dev/null 2>&1
+    bind --mode insert \e: emojicp-widget
+end
@@ -0,0 +1,307 @@
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+// What is wrong with a style in the `[theme]` section
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ThemeError {
+    #[error("unknown color `{0}`")]
+    UnknownColor(String),
+
+    #[error("missing background color in `{0}`")]
+    MissingBackground(String),
+}
+
+// The built-in themes a configuration can start from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeName {
+    #[default]
+    Default,
+    HighContrast,
+    Monochrome,
+    Solarized,
+}
+
+// Whether the picker is drawn in color. `Auto` uses colors unless the
+// `NO_COLOR` environment variable is set
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum ColorMode {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    // `enabled` tells whether colors are used, given whether `NO_COLOR` is
+    // set to something
+    pub fn enabled(self, no_color: bool) -> bool {
+        match self {
+            ColorMode::Auto => !no_color,
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        }
+    }
+}
+
+// The `[theme]` section of the configuration file. Each element left out
+// keeps the style of the named theme; the rest are written as e.g.
+// `"bold yellow on blue"`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub name: ThemeName,
+    pub input: Option<String>,
+    pub list: Option<String>,
+    pub highlight: Option<String>,
+    pub borders: Option<String>,
+    pub status: Option<String>,
+    pub error: Option<String>,
+}
+
+// The styles the picker is drawn with
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Theme {
+    // The search box while typing in it
+    pub input: Style,
+
+    // The suggestions while one of them is highlighted
+    pub list: Style,
+
+    // The highlighted suggestion
+    pub highlight: Style,
+
+    // The borders around each part of the picker
+    pub borders: Style,
+
+    // The hints and messages beneath the suggestions
+    pub status: Style,
+
+    // What is wrong with the search term, beneath the search box
+    pub error: Style,
+}
+
+impl Theme {
+    pub fn named(name: ThemeName) -> Theme {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+
+        match name {
+            ThemeName::Default => Theme {
+                input: Style::default().fg(Color::Yellow),
+                list: Style::default().fg(Color::Yellow),
+                highlight: bold,
+                error: Style::default().fg(Color::Red),
+                ..Default::default()
+            },
+            ThemeName::HighContrast => Theme {
+                input: bold.fg(Color::White),
+                list: bold.fg(Color::White),
+                highlight: bold.fg(Color::Black).bg(Color::LightYellow),
+                borders: Style::default().fg(Color::White),
+                status: bold.fg(Color::LightCyan),
+                error: bold.fg(Color::LightRed),
+            },
+            ThemeName::Monochrome => Theme {
+                input: bold,
+                list: Style::default(),
+                highlight: bold.add_modifier(Modifier::REVERSED),
+                error: bold,
+                ..Default::default()
+            },
+            ThemeName::Solarized => Theme {
+                input: Style::default().fg(Color::Rgb(0xb5, 0x89, 0x00)),
+                list: Style::default().fg(Color::Rgb(0x83, 0x94, 0x96)),
+                highlight: bold
+                    .fg(Color::Rgb(0xfd, 0xf6, 0xe3))
+                    .bg(Color::Rgb(0x26, 0x8b, 0xd2)),
+                borders: Style::default().fg(Color::Rgb(0x58, 0x6e, 0x75)),
+                status: Style::default().fg(Color::Rgb(0x2a, 0xa1, 0x98)),
+                error: Style::default().fg(Color::Rgb(0xdc, 0x32, 0x2f)),
+            },
+        }
+    }
+
+    // `new` builds the theme described by the `[theme]` section. Without
+    // `color` every color is dropped, leaving only bold, reversed and the
+    // like
+    pub fn new(config: &ThemeConfig, color: bool) -> Result<Theme, ThemeError> {
+        let mut theme = Theme::named(config.name);
+
+        for (style, spec) in [
+            (&mut theme.input, &config.input),
+            (&mut theme.list, &config.list),
+            (&mut theme.highlight, &config.highlight),
+            (&mut theme.borders, &config.borders),
+            (&mut theme.status, &config.status),
+            (&mut theme.error, &config.error),
+        ] {
+            if let Some(spec) = spec {
+                *style = parse_style(spec)?;
+            }
+        }
+
+        if !color {
+            for style in [
+                &mut theme.input,
+                &mut theme.list,
+                &mut theme.highlight,
+                &mut theme.borders,
+                &mut theme.status,
+                &mut theme.error,
+            ] {
+                style.fg = None;
+                style.bg = None;
+            }
+        }
+
+        Ok(theme)
+    }
+}
+
+// `parse_style` reads a style such as `"bold yellow on blue"`: any number
+// of modifiers, a foreground color and `on` followed by a background color,
+// in any order. `"none"` is the terminal's own style
+pub fn parse_style(spec: &str) -> Result<Style, ThemeError> {
+    let mut style = Style::default();
+    let mut words = spec.split_whitespace();
+
+    while let Some(word) = words.next() {
+        style = match word.to_lowercase().as_str() {
+            "none" => style,
+            "bold" => style.add_modifier(Modifier::BOLD),
+            "dim" => style.add_modifier(Modifier::DIM),
+            "italic" => style.add_modifier(Modifier::ITALIC),
+            "underlined" => style.add_modifier(Modifier::UNDERLINED),
+            "reversed" => style.add_modifier(Modifier::REVERSED),
+            "crossed_out" => style.add_modifier(Modifier::CROSSED_OUT),
+            "on" => {
+                let color = words
+                    .next()
+                    .ok_or_else(|| ThemeError::MissingBackground(spec.to_owned()))?;
+                style.bg(parse_color(color)?)
+            }
+            _ => style.fg(parse_color(word)?),
+        };
+    }
+
+    Ok(style)
+}
+
+// Colors are the names of the 16 terminal colors, e.g. `light_blue`, an
+// index into the 256-color palette or a `#rrggbb` hex code
+fn parse_color(word: &str) -> Result<Color, ThemeError> {
+    let color = match word.to_lowercase().replace('-', "_").as_str() {
+        "reset" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" => Color::Gray,
+        "dark_gray" => Color::DarkGray,
+        "light_red" => Color::LightRed,
+        "light_green" => Color::LightGreen,
+        "light_yellow" => Color::LightYellow,
+        "light_blue" => Color::LightBlue,
+        "light_magenta" => Color::LightMagenta,
+        "light_cyan" => Color::LightCyan,
+        "white" => Color::White,
+        hex if hex.starts_with('#') && hex.len() == 7 && hex.is_ascii() => {
+            let channel = |range| u8::from_str_radix(&hex[range], 16);
+            match (channel(1..3), channel(3..5), channel(5..7)) {
+                (Ok(r), Ok(g), Ok(b)) => Color::Rgb(r, g, b),
+                _ => return Err(ThemeError::UnknownColor(word.to_owned())),
+            }
+        }
+        index => Color::Indexed(
+            index
+                .parse()
+                .map_err(|_| ThemeError::UnknownColor(word.to_owned()))?,
+        ),
+    };
+
+    Ok(color)
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::{Color, Modifier, Style};
+
+    use crate::theme::{parse_style, ColorMode, Theme, ThemeConfig, ThemeError, ThemeName};
+
+    #[test]
+    fn test_parse_style() {
+        assert_eq!(Ok(Style::default()), parse_style("none"));
+        assert_eq!(
+            Ok(Style::default()
+                .fg(Color::Yellow)
+                .bg(Color::Blue)
+                .add_modifier(Modifier::BOLD)),
+            parse_style("bold yellow on blue")
+        );
+        assert_eq!(
+            Ok(Style::default()
+                .fg(Color::Rgb(0x26, 0x8b, 0xd2))
+                .bg(Color::Indexed(236))),
+            parse_style("#268bd2 on 236")
+        );
+        assert_eq!(
+            Ok(Style::default().fg(Color::LightBlue)),
+            parse_style("Light-Blue")
+        );
+
+        assert_eq!(
+            Err(ThemeError::UnknownColor(String::from("chartreuse"))),
+            parse_style("chartreuse")
+        );
+        assert_eq!(
+            Err(ThemeError::MissingBackground(String::from("bold on"))),
+            parse_style("bold on")
+        );
+        assert!(parse_style("#12345g").is_err());
+    }
+
+    #[test]
+    fn test_theme() {
+        // elements left out keep the style of the named theme
+        let config = ThemeConfig {
+            name: ThemeName::Solarized,
+            status: Some(String::from("italic red")),
+            ..Default::default()
+        };
+        let theme = Theme::new(&config, true).unwrap();
+        assert_eq!(Theme::named(ThemeName::Solarized).input, theme.input);
+        assert_eq!(
+            Style::default()
+                .fg(Color::Red)
+                .add_modifier(Modifier::ITALIC),
+            theme.status
+        );
+
+        // without colors only the modifiers are left
+        let theme = Theme::new(&config, false).unwrap();
+        assert_eq!(None, theme.highlight.bg);
+        assert_eq!(None, theme.status.fg);
+        assert!(theme.highlight.add_modifier.contains(Modifier::BOLD));
+
+        let config = ThemeConfig {
+            borders: Some(String::from("blinking")),
+            ..Default::default()
+        };
+        assert!(Theme::new(&config, true).is_err());
+    }
+
+    #[test]
+    fn test_color_mode() {
+        assert!(ColorMode::Auto.enabled(false));
+        assert!(!ColorMode::Auto.enabled(true));
+        assert!(ColorMode::Always.enabled(true));
+        assert!(!ColorMode::Never.enabled(false));
+    }
+}
@@ -1,149 +1,199 @@
-use std::default;
+use std::path::PathBuf;
 
+use crate::clipboard::ClipboardBackend;
+use crate::config::{OutputMode, View};
 use crate::constants::ABOUT_DESCRIPTION;
-use crate::emoji::Emoji;
-use crate::pair::EmojiPair;
-use bk_tree::BKTree;
-use clap::Parser;
-use std::io::{stdin, stdout, Stdout, Write};
-use termion::event::Key;
-use termion::input::TermRead;
-use termion::raw::IntoRawMode;
-use termion::raw::RawTerminal;
-use thiserror::Error;
+use crate::keymap::Preset;
+use crate::pair::{SkinTone, Version};
+use crate::shell::Shell;
+use crate::theme::{ColorMode, ThemeName};
+use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(version)]
 #[command(about = ABOUT_DESCRIPTION, long_about=None)]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(disable_help_subcommand = true)]
 pub struct Args {
-    pub description: Option<String>,
-}
+    /// Names of the emojis to copy, e.g. `tada rocket sparkles`
+    #[arg(value_name = "NAME")]
+    pub names: Vec<String>,
+
+    #[command(flatten)]
+    pub overrides: ConfigOverrides,
 
-// `Carousel` trait is a generic interface for allowing the user to search for
-// an item, presenting the user with a list of items, and allowing the user to
-// select an item from that list.
-pub trait Carousel {
-    // The type of data that the user can search for and select from the
-    // `Carousel`
-    type Item;
-
-    // `new` returns a new instance of the `Carousel` type. The list of items
-    // will be displayed to the user by writing to `display`. In a terminal
-    // environment, `display` will typically be `std::io::stdout`. Note that the
-    // `Carousel` will not start displaying items or allow the user to search
-    // for or select an item until `start` has been called
-    fn new(display: &impl std::io::Write) -> Self;
-
-    // `start` initializes the `Carousel` object, allowing the user to start
-    // searching for and selecting items from the `Carousel`. Calling `start`
-    // rotates the `Carousel` to the default location
-    fn start(&mut self);
-
-    // `search` the `Carousel` for `item`. Calling `search` updates the list of
-    // items displayed to the user with the items that most closely match
-    // `item`. Calling `search` also rotates the `Carousel` to the default
-    // location
-    fn search(&mut self, item: &Self::Item);
-
-    // `next` rotates the `Carousel` to the next item in the list presented to
-    // the user. If the `Carousel` has already been rotated to the end of the
-    // list, then calling this method does nothing
-    fn next(&mut self);
-
-    // `previous` rotates the `Carousel` to the previous item in the list
-    // presented to the user. If the `Carousel` has already been rotated to the
-    // beginning of the list, then calling this method does nothing
-    fn previous(&mut self);
-
-    // `select` returns the item currently selected in the list of items
-    // presented to the user
-    fn select(&mut self) -> Option<&Self::Item>;
-
-    // `quit` clears writer and exits the loop started when `search` was called
-    fn quit(&mut self);
+    #[command(subcommand)]
+    pub command: Option<Command>,
 }
 
-// Where the cursor is in the terminal
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Coordinates {
-    pub x: usize,
-    pub y: usize,
+// Command-line flags that take precedence over both the configuration file
+// and the `EMOJICP_*` environment variables
+#[derive(clap::Args, Debug, Default)]
+pub struct ConfigOverrides {
+    /// Read the configuration from PATH instead of the default location
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Where to send the selected emoji
+    #[arg(short, long, global = true, value_name = "MODE")]
+    pub output: Option<OutputMode>,
+
+    /// How to copy the selected emoji to the clipboard
+    #[arg(long, global = true, value_name = "BACKEND")]
+    pub clipboard_backend: Option<ClipboardBackend>,
+
+    /// Skin tone to use for emojis that support one
+    #[arg(long, global = true, value_name = "TONE")]
+    pub skin_tone: Option<SkinTone>,
+
+    /// Text placed between emojis when several are copied at once
+    #[arg(short, long, global = true, value_name = "TEXT")]
+    pub separator: Option<String>,
+
+    /// Keep the interactive picker open after copying an emoji
+    #[arg(long, global = true, overrides_with = "no_keep_open")]
+    pub keep_open: bool,
+
+    /// Close the interactive picker after copying an emoji, even if the
+    /// configuration keeps it open
+    #[arg(long, global = true, overrides_with = "keep_open")]
+    pub no_keep_open: bool,
+
+    /// Draw the interactive picker in ROWS lines beneath the prompt instead
+    /// of taking over the whole terminal
+    #[arg(long, global = true, value_name = "ROWS")]
+    pub height: Option<u16>,
+
+    /// Lay the suggestions out as a list or as a grid of glyphs
+    #[arg(long, global = true, value_name = "VIEW")]
+    pub view: Option<View>,
+
+    /// Columns an emoji's name takes up in the list of suggestions; longer
+    /// names are cut short
+    #[arg(long, global = true, value_name = "COLUMNS")]
+    pub name_width: Option<usize>,
+
+    /// Check whether the terminal draws emoji sequences such as 👩‍🦰 as a
+    /// single emoji, even if the configuration turns the check off
+    #[arg(long, global = true, overrides_with = "no_render_check")]
+    pub render_check: bool,
+
+    /// Don't check whether the terminal draws emoji sequences such as 👩‍🦰 as
+    /// a single emoji
+    #[arg(long, global = true, overrides_with = "render_check")]
+    pub no_render_check: bool,
+
+    /// Leave out emojis introduced after this Emoji version, e.g. 12.0, for
+    /// systems that show newer ones as boxes
+    #[arg(long, global = true, value_name = "VERSION")]
+    pub max_version: Option<Version>,
+
+    /// Number of suggestions shown in interactive mode
+    #[arg(short = 'n', long, global = true, value_name = "COUNT")]
+    pub results: Option<usize>,
+
+    /// Maximum edit distance for suggestions in interactive mode
+    #[arg(long, global = true, value_name = "DISTANCE")]
+    pub tolerance: Option<u32>,
+
+    /// Built-in key bindings to use in interactive mode
+    #[arg(long, global = true, value_name = "PRESET")]
+    pub keymap: Option<Preset>,
+
+    /// Draw the interactive picker in color: auto, always or never. auto
+    /// turns colors off when NO_COLOR is set
+    #[arg(long, global = true, value_name = "WHEN")]
+    pub color: Option<ColorMode>,
+
+    /// Built-in theme of the interactive picker
+    #[arg(long, global = true, value_name = "THEME")]
+    pub theme: Option<ThemeName>,
 }
 
-impl Coordinates {
-    fn x(&self) -> u16 {
-        self.x as u16
+impl ConfigOverrides {
+    // `keep_open` is what `--keep-open` or `--no-keep-open` asks for, if
+    // either was passed. The last one passed wins
+    pub fn keep_open(&self) -> Option<bool> {
+        switch(self.keep_open, self.no_keep_open)
     }
 
-    fn y(&self) -> u16 {
-        self.y as u16
+    // `render_check` is what `--render-check` or `--no-render-check` asks
+    // for, if either was passed
+    pub fn render_check(&self) -> Option<bool> {
+        switch(self.render_check, self.no_render_check)
     }
 }
 
-// What mode the application is in
-#[derive(PartialEq)]
-pub enum ApplicationMode {
-    // The user is typing characters to search for an emoji
+// `switch` reads a pair of flags that turn a setting on and off
+fn switch(on: bool, off: bool) -> Option<bool> {
+    match (on, off) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Print the emoji and name of every emoji matching a search query, e.g.
+    /// `emojicp search cat:food '^b'`. Every term has to match: a word of
+    /// the name, a "quoted phrase", ^prefix, suffix$, cat:CATEGORY,
+    /// kw:KEYWORD, v:VERSION (or v:<=VERSION and the like), tone:yes or
+    /// tone:no, and !TERM to leave out what TERM matches
     Search {
-        // The position of the cursor when the user is searching
-        // for an emoji
-        search_cursor_pos: Coordinates,
-    },
+        #[arg(value_name = "QUERY", required = true)]
+        query: Vec<String>,
 
-    // The user is selecting the desired emoji from the displayed options
-    Select {
-        // The currently selected emoji from the list
-        current_selection: u8,
+        /// Treat the query as a regular expression matched against every
+        /// name and alias, e.g. `emojicp search --regex '_moon$'`
+        #[arg(long)]
+        regex: bool,
+    },
 
-        // The position of the cursor when the user is selecting
-        // an emoji from the list
-        select_cursor_pos: Coordinates,
+    /// Inspect or create the configuration file
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
     },
-}
 
-pub struct EmojiCarousel<W>
-where
-    W: std::io::Write,
-{
-    // The interface that is written to in order
-    // to display each emoji and its description
-    pub display: W,
-
-    // The BKTree used for fuzzy searches
-    pub tree: BKTree<EmojiPair>,
-
-    // What mode the application is in, i.e. if the user
-    // is typing to perform a search or selecting an emoji
-    // from the available options
-    pub mode: ApplicationMode,
-
-    // The characters the user has typed in thusfar. If
-    // `None`, then the user has yet to start searchng
-    // or the carousel has been reset
-    pub search_term: Option<String>,
-
-    // List of suggested emojis and their names
-    // currently being shown to the user
-    pub suggestions: Vec<EmojiPair>,
-}
+    /// Print a shell widget that inserts an emoji at the cursor, e.g.
+    /// `eval "$(emojicp shell-init zsh)"`
+    ShellInit {
+        #[arg(value_name = "SHELL")]
+        shell: Shell,
+    },
 
-#[derive(Error, Debug)]
-pub enum EmojiError {
-    #[error("supplied writer cannot enter raw mode")]
-    CannotEnterRawMode,
+    /// Print a completion script that also completes emoji names, e.g.
+    /// `emojicp completions fish > ~/.config/fish/completions/emojicp.fish`
+    Completions {
+        #[arg(value_name = "SHELL")]
+        shell: clap_complete::Shell,
+    },
 
-    #[error("cannot open file `{filename:?}`")]
-    IoError { filename: String },
+    /// Print the manual page in roff format, e.g.
+    /// `emojicp manpage > ~/.local/share/man/man1/emojicp.1`
+    Manpage,
 
-    #[error("cannot deserialize BKTree from file `{filename:?}`")]
-    CannotDeserializeBKTree { filename: String },
+    /// Print help for emojicp or one of its subcommands, or `help keys` to
+    /// list the key bindings of the interactive picker
+    Help {
+        #[arg(value_name = "TOPIC")]
+        topic: Vec<String>,
+    },
+}
 
-    #[error("cannot find the emoji `{description:?}`")]
-    InvalidEmojiName { description: String },
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Print the effective configuration as TOML
+    Show,
 
-    #[error("cannot copy emoji `{emoji:?}` to the clipboard")]
-    CannotCopyEmojiToClipboard { emoji: String },
+    /// Print the path of the configuration file
+    Path,
 
-    #[error("user canceled program; nothing copied to clipboard")]
-    UserCanceledProgram,
+    /// Write a configuration file containing the default settings
+    Init {
+        /// Overwrite the configuration file if it already exists
+        #[arg(long)]
+        force: bool,
+    },
 }
@@ -1,210 +1,764 @@
-use std::{
-    error::Error,
-    io,
-    time::{Duration, Instant},
-};
+use std::time::{Duration, Instant};
 
-use crate::clipboard;
-use crate::emoji::Emoji;
-use crate::pair::*;
-use crate::types::*;
+use crate::carousel::{Carousel, EmojiCarousel};
+use crate::clipboard::{self, ClipboardConfig};
+use crate::config::{Config, OutputMode, View};
+use crate::constants::*;
+use crate::error::Error;
+use crate::favorites::Favorites;
+use crate::grid::{self, Grid, Row};
+use crate::history::History;
+use crate::input::LineInput;
+use crate::keymap::{Action, Context, KeyBinding, KeyMap};
+use crate::pair::{self, *};
+#[cfg(feature = "regex")]
+use crate::query::regex_message;
+use crate::query::Query;
+use crate::render::{Probe, RenderCheck};
+use crate::search::{emoji_list, emoji_tree};
+use crate::theme::Theme;
+use crate::width;
 use bk_tree::BKTree;
-use clipboard_anywhere::set_clipboard;
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind,
 };
-use ratatui::backend::CrosstermBackend;
 use ratatui::widgets::*;
 use ratatui::{
-    backend::{self, Backend},
-    buffer::{self, Buffer},
-    layout::{self, Alignment, Constraint, Corner, Direction, Layout, Margin, Rect},
-    style::{self, Color, Modifier, Style, *},
-    symbols::{self, Marker},
-    terminal::{self, Frame, Terminal, TerminalOptions, Viewport},
-    text::{self, Line, Masked, Span, Text},
+    backend::Backend,
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    terminal::{Frame, Terminal},
+    text::{Line, Span},
 };
+#[cfg(feature = "regex")]
+use regex::Regex;
+use unicode_width::UnicodeWidthStr;
 
 enum InputMode {
     Searching,
     Selecting,
 }
 
-struct EmojiSuggestions<T>
-where
-    T: std::fmt::Display,
-{
-    bk_tree: BKTree<T>,
-    state: ListState,
-    items: Vec<T>,
+// What a mouse click landed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    Input,
+
+    // The suggestion at this index
+    Emoji(usize),
+
+    // A category tab or header of the grid, by the index of the category's
+    // first emoji
+    Category(usize),
+}
+
+// The emojis suggested when the user has no favorites yet
+const SAMPLE_NAMES: [&str; 5] = ["crab", "snake", "monkey", "eyeglasses", "coffee"];
+
+pub struct App {
+    carousel: EmojiCarousel,
+
+    // Where the suggestion list is scrolled to, kept between frames
+    list: ListState,
+
+    view: View,
+
+    // Columns each name takes up in the list, before its emoji
+    name_width: usize,
+
+    // The name of every emoji with a distinct glyph, grouped by category,
+    // which the grid shows while the search box is empty
+    catalog: Vec<String>,
+
+    // How many emojis fit in a row of the grid, the first row shown and
+    // how many rows are shown beneath the category tabs, all updated
+    // whenever the grid is drawn
+    grid_columns: usize,
+    grid_offset: usize,
+    grid_height: usize,
+
+    // Where the search box and the suggestions were last drawn, to tell
+    // what a mouse click landed on
+    input_area: Rect,
+    suggestions_area: Rect,
+
+    // The suggestion last clicked and when, to tell a double click apart
+    last_click: Option<(usize, Instant)>,
+
+    // Which emoji sequences the terminal draws as a single emoji
+    render_check: RenderCheck,
+
     mode: InputMode,
-    user_input: String,
-    user_input_changed: bool,
-    cursor_position: usize,
+    input: LineInput,
+
+    // What is wrong with the search term if it isn't a valid query
+    query_error: Option<String>,
+
+    // Whether the search term is a regular expression rather than a query
+    #[cfg(feature = "regex")]
+    regex: bool,
+
+    favorites: Favorites,
+
+    // Emojis the user has marked to be copied together, in marking order
+    marked: Vec<EmojiPair>,
+
+    keymap: KeyMap,
+    theme: Theme,
+    prompt: String,
+    skin_tone: SkinTone,
+    separator: String,
+    output: OutputMode,
+    clipboard: ClipboardConfig,
+
+    // Whether `copy` keeps the picker open like `copy_and_stay` does
+    keep_open: bool,
+
+    // Whether the detail pane is shown when the terminal is wide enough
+    details: bool,
+
+    // How far the help overlay is scrolled down while it is open
+    help: Option<usize>,
+
+    // How many suggestions the list shows, restored when leaving the grid
+    result_count: usize,
+
+    // Everything copied while the picker was kept open, in copying order
+    copied: Vec<EmojiPair>,
+
+    // A short message for the user and when it was shown
+    status: Option<(String, Instant)>,
 }
 
-impl EmojiSuggestions<EmojiPair> {
-    fn new() -> Self {
-        let emoji_file = Emoji::get("static/emojitree.raw").unwrap();
-        let encoded_tree = emoji_file.data.as_ref();
-        let tree: BKTree<EmojiPair> = bincode::deserialize(encoded_tree).unwrap();
+impl App {
+    pub fn new(config: &Config) -> Result<Self, Error> {
+        App::with(
+            config,
+            emoji_tree()?,
+            &emoji_list()?,
+            History::load(),
+            Favorites::load(),
+        )
+    }
+
+    // `with` builds the picker around the given emojis, history and
+    // favorites rather than the ones on disk
+    fn with(
+        config: &Config,
+        tree: BKTree<EmojiPair>,
+        list: &[EmojiPair],
+        history: History,
+        favorites: Favorites,
+    ) -> Result<Self, Error> {
+        let keymap = config
+            .keymap()
+            .map_err(|source| Error::InvalidKeybindings { source })?;
+        let theme = config
+            .theme()
+            .map_err(|source| Error::InvalidTheme { source })?;
+
+        let mut carousel =
+            EmojiCarousel::new(tree, config.search.tolerance, config.search.result_count);
+        carousel.set_max_version(config.max_version);
 
-        EmojiSuggestions {
-            bk_tree: tree,
-            state: ListState::default(),
-            items: Vec::new(),
+        let mut distinct: Vec<EmojiPair> = Vec::new();
+        for pair in list {
+            if !distinct.iter().any(|known| known.emoji == pair.emoji) {
+                distinct.push(pair.clone());
+            }
+        }
+        let catalog = grid::by_category(&distinct)
+            .into_iter()
+            .map(|pair| pair.description)
+            .collect();
+
+        let mut app = App {
+            carousel,
+            list: ListState::default(),
+            view: View::List,
+            name_width: config.name_width,
+            catalog,
+            grid_columns: 1,
+            grid_offset: 0,
+            grid_height: 0,
+            input_area: Rect::default(),
+            suggestions_area: Rect::default(),
+            last_click: None,
+            render_check: RenderCheck::new(config.render_check),
             mode: InputMode::Searching,
-            user_input: String::from(""),
-            user_input_changed: true,
-            cursor_position: 0,
+            input: LineInput::new(history),
+            query_error: None,
+            #[cfg(feature = "regex")]
+            regex: false,
+            favorites,
+            marked: Vec::new(),
+            keymap,
+            theme,
+            prompt: config.prompt.clone(),
+            skin_tone: config.skin_tone,
+            separator: config.separator.clone(),
+            output: config.output,
+            clipboard: config.clipboard.clone(),
+            keep_open: config.keep_open,
+            details: true,
+            help: None,
+            result_count: config.search.result_count,
+            copied: Vec::new(),
+            status: None,
+        };
+
+        app.show_favorites();
+        if app.carousel.items().is_empty() {
+            app.carousel.show(&SAMPLE_NAMES);
         }
+
+        if config.view == View::Grid {
+            app.toggle_view();
+        }
+
+        Ok(app)
     }
 
-    fn next(&mut self) {
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i + 1 >= self.items.len() {
-                    0
-                } else {
-                    i + 1
-                }
+    // `refresh` updates the suggestions after the user input has changed.
+    // With nothing typed in, the list suggests the user's favorites and the
+    // grid shows every emoji instead
+    fn refresh(&mut self) {
+        let user_input = self.input.text().to_owned();
+        self.query_error = self.query_error(&user_input);
+
+        match (self.view, user_input.is_empty()) {
+            (View::List, true) => self.show_favorites(),
+            (View::List, false) => self.carousel.search(&user_input),
+            (View::Grid, true) => self.carousel.show(&self.catalog),
+            (View::Grid, false) => {
+                // The grid has a header for each category, so the results
+                // are kept together by category
+                self.carousel.search(&user_input);
+                let names: Vec<String> = grid::by_category(self.carousel.items())
+                    .into_iter()
+                    .map(|pair| pair.description)
+                    .collect();
+                self.carousel.show(&names);
             }
-            None => 0,
+        }
+    }
+
+    // `query_error` says what is wrong with `text` as a search term, if
+    // anything
+    fn query_error(&self, text: &str) -> Option<String> {
+        #[cfg(feature = "regex")]
+        if self.regex {
+            return Regex::new(text).err().map(|error| regex_message(&error));
+        }
+
+        text.parse::<Query>().err().map(|error| error.to_string())
+    }
+
+    // `toggle_view` switches between the list and the grid. The grid has
+    // room for many more search results than the list
+    fn toggle_view(&mut self) {
+        let (view, result_count) = match self.view {
+            View::List => (View::Grid, GRID_RESULT_COUNT),
+            View::Grid => (View::List, self.result_count),
         };
 
-        self.state.select(Some(i));
+        self.view = view;
+        self.carousel.set_result_count(result_count);
+        self.mode = InputMode::Searching;
+        self.grid_offset = 0;
+        self.refresh();
     }
 
-    fn previous(&mut self) {
-        let i = match self.state.selected() {
-            Some(i) => {
-                if 0 == i {
-                    self.items.len().saturating_sub(1)
-                } else {
-                    i - 1
-                }
+    // `move_vertically` highlights the emoji above or below the highlighted
+    // one in the grid. Moving up from the top row returns to the search box
+    fn move_vertically(&mut self, down: bool) {
+        let Some(position) = self.carousel.position() else {
+            self.mode = InputMode::Selecting;
+            if down {
+                self.carousel.next();
+            } else {
+                self.carousel.previous();
             }
-            None => self.items.len().saturating_sub(1),
+            return;
         };
 
-        self.state.select(Some(i));
+        let grid = Grid::new(self.carousel.items(), self.grid_columns);
+        let target = if down {
+            grid.below(position)
+        } else {
+            grid.above(position)
+        };
+
+        match (target, down) {
+            (Some(index), _) => self.carousel.rotate_to(index),
+            (None, true) => {}
+            (None, false) => {
+                self.mode = InputMode::Searching;
+                self.carousel.unselect();
+            }
+        }
     }
 
-    fn unselect(&mut self) {
-        self.state.select(None);
+    fn show_favorites(&mut self) {
+        self.carousel.show(self.favorites.names());
     }
 
-    fn select(&mut self) -> Option<&EmojiPair> {
-        let Some(index) = self.state.selected() else {
-            return None;
+    fn toggle_mark(&mut self) {
+        let Some(pair) = self.carousel.select().cloned() else {
+            return;
         };
 
-        let Some(item) = self.items.get(index) else {
-            return None;
+        if self.is_marked(&pair.description) {
+            self.marked
+                .retain(|marked| marked.description != pair.description);
+        } else {
+            self.marked.push(pair);
+        }
+    }
+
+    fn is_marked(&self, name: &str) -> bool {
+        self.marked.iter().any(|marked| marked.description == name)
+    }
+
+    // `grid_highlight` is the style of the highlighted emoji in the grid. A
+    // lone glyph in bold can't be told apart from the others, so it is
+    // reversed unless the theme gives it a background of its own
+    fn grid_highlight(&self) -> Style {
+        if self.theme.highlight.bg.is_some() {
+            self.theme.highlight
+        } else {
+            self.theme.highlight.add_modifier(Modifier::REVERSED)
+        }
+    }
+
+    // `grid_glyph` is the emoji drawn in the grid for `pair`. There is no
+    // room for code points there, so only the first emoji of a sequence the
+    // terminal can't draw is shown
+    fn grid_glyph(&self, pair: &EmojiPair) -> String {
+        let glyph = pair.with_skin_tone(self.skin_tone);
+        if self.render_check.renders(glyph) {
+            glyph.to_owned()
+        } else {
+            glyph.chars().take(1).collect()
+        }
+    }
+
+    // `detail_glyph` is the emoji shown in the detail pane, or its code
+    // points if the terminal can't draw it
+    fn detail_glyph(&self, pair: &EmojiPair) -> String {
+        let glyph = pair.with_skin_tone(self.skin_tone);
+        if self.render_check.renders(glyph) {
+            glyph.to_owned()
+        } else {
+            pair::codepoints(glyph)
+        }
+    }
+
+    // `visible_glyphs` lists the emojis on screen as they are drawn: the
+    // suggestions in the list or the rows of the grid that are shown, and
+    // the highlighted emoji in the detail pane
+    fn visible_glyphs(&self) -> Vec<&str> {
+        let items = self.carousel.items();
+        let mut glyphs: Vec<&str> = match self.view {
+            View::List => items.iter().map(|pair| pair.emoji.as_str()).collect(),
+            View::Grid => {
+                let grid = Grid::new(items, self.grid_columns);
+                let shown = grid
+                    .rows
+                    .iter()
+                    .skip(self.grid_offset)
+                    .take(self.grid_height)
+                    .flat_map(|row| match row {
+                        Row::Cells { start, end } => *start..*end,
+                        Row::Header(_) => 0..0,
+                    });
+
+                grid.categories()
+                    .into_iter()
+                    .chain(shown)
+                    .map(|index| items[index].with_skin_tone(self.skin_tone))
+                    .collect()
+            }
+        };
+
+        if let Some(pair) = self.carousel.select() {
+            glyphs.push(pair.with_skin_tone(self.skin_tone));
+        }
+
+        glyphs
+    }
+
+    fn toggle_favorite(&mut self) {
+        let Some(name) = self.carousel.select().map(|pair| pair.description.clone()) else {
+            return;
         };
 
-        println!("Found item: {}", item);
-        Some(item)
+        // Failing to save the favorites shouldn't interrupt the search; the
+        // change still applies for the rest of the session
+        let _ = self.favorites.toggle(&name);
+    }
+
+    fn context(&self) -> Context {
+        match self.mode {
+            InputMode::Searching => Context::Input,
+            InputMode::Selecting => match self.view {
+                View::List => Context::List,
+                View::Grid => Context::Grid,
+            },
+        }
+    }
+
+    // `handle_event` returns the emojis the user picked once they are done
+    fn handle_event(&mut self, event: Event) -> Result<Option<Vec<EmojiPair>>, Error> {
+        if let Some(scroll) = self.help {
+            self.handle_help_event(event, scroll);
+            return Ok(None);
+        }
+
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => self.handle_key(key),
+            Event::Paste(pasted) => {
+                self.handle_paste(&pasted);
+                Ok(None)
+            }
+            Event::Mouse(mouse) => self.handle_mouse(mouse),
+            _ => Ok(None),
+        }
+    }
+
+    // `handle_mouse` highlights the suggestion clicked on and copies it when
+    // it is clicked twice in a row. The wheel moves through the suggestions
+    // like the arrow keys do
+    fn handle_mouse(&mut self, mouse: MouseEvent) -> Result<Option<Vec<EmojiPair>>, Error> {
+        match mouse.kind {
+            MouseEventKind::ScrollDown => return self.perform(Action::SelectNext),
+            MouseEventKind::ScrollUp => return self.perform(Action::SelectPrev),
+            MouseEventKind::Down(MouseButton::Left) => {}
+            _ => return Ok(None),
+        }
+
+        let target = self.target_at(mouse.column, mouse.row);
+        let last_click = self.last_click.take();
+        match target {
+            Some(Target::Input) => return self.perform(Action::Unselect),
+            Some(Target::Emoji(index)) => {
+                self.mode = InputMode::Selecting;
+                self.carousel.rotate_to(index);
+
+                let double_click = last_click.is_some_and(|(last, clicked)| {
+                    last == index && clicked.elapsed() < Duration::from_millis(DOUBLE_CLICK_MS)
+                });
+                if double_click {
+                    return self.perform(Action::Copy);
+                }
+
+                self.last_click = Some((index, Instant::now()));
+            }
+            Some(Target::Category(index)) => {
+                self.mode = InputMode::Selecting;
+                self.carousel.rotate_to(index);
+
+                // The category is scrolled to the top, header and all
+                let grid = Grid::new(self.carousel.items(), self.grid_columns);
+                if let Some(row) = grid.row_of(index) {
+                    self.grid_offset = row.saturating_sub(1);
+                }
+            }
+            None => {}
+        }
+
+        Ok(None)
     }
 
-    fn search(&mut self, item: &String) {
-        // step #1: clear current suggestions
-        self.items.clear();
+    // `target_at` finds what was drawn at `x, y` on the last frame
+    fn target_at(&self, x: u16, y: u16) -> Option<Target> {
+        if contains(self.input_area, x, y) {
+            return Some(Target::Input);
+        }
+
+        let inner = self.suggestions_area.inner(&Margin {
+            vertical: 1,
+            horizontal: 1,
+        });
+        if !contains(inner, x, y) {
+            return None;
+        }
+
+        let items = self.carousel.items();
+        let line = (y - inner.y) as usize;
+        match self.view {
+            View::List => {
+                let index = self.list.offset() + line;
+                (index < items.len()).then_some(Target::Emoji(index))
+            }
+            View::Grid => {
+                let grid = Grid::new(items, self.grid_columns);
+                let column = ((x - inner.x) / GRID_CELL_WIDTH) as usize;
+
+                // The first line holds the category tabs
+                let Some(row) = line.checked_sub(1) else {
+                    return grid.categories().get(column).copied().map(Target::Category);
+                };
+
+                let row = self.grid_offset + row;
+                match grid.rows.get(row)? {
+                    Row::Header(_) => match grid.rows.get(row + 1)? {
+                        Row::Cells { start, .. } => Some(Target::Category(*start)),
+                        Row::Header(_) => None,
+                    },
+                    Row::Cells { start, end } => {
+                        let index = start + column;
+                        (index < *end).then_some(Target::Emoji(index))
+                    }
+                }
+            }
+        }
+    }
 
-        // step #2: perform search on tree
-        let tolerance = 10;
-        let key = EmojiPair {
-            description: item.clone(),
-            emoji: "".to_owned(), // doesn't matter for search
+    // `handle_help_event` scrolls the help overlay with the keys that move
+    // through the suggestions or the mouse wheel, and closes it with `Esc` or
+    // the keys that open it. Everything else is ignored while it is open
+    fn handle_help_event(&mut self, event: Event, scroll: usize) {
+        let action = match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                if key.code == KeyCode::Esc {
+                    Some(Action::Cancel)
+                } else {
+                    self.keymap.action(self.context(), KeyBinding::from(key))
+                }
+            }
+            Event::Mouse(mouse) => match mouse.kind {
+                MouseEventKind::ScrollDown => Some(Action::SelectNext),
+                MouseEventKind::ScrollUp => Some(Action::SelectPrev),
+                _ => None,
+            },
+            _ => None,
         };
 
-        let search_results = self.bk_tree.find(&key, tolerance);
+        self.help = match action {
+            Some(Action::Cancel | Action::ShowHelp) => None,
+            Some(Action::SelectNext) => Some(scroll + 1),
+            Some(Action::SelectPrev) => Some(scroll.saturating_sub(1)),
+            _ => Some(scroll),
+        };
+    }
 
-        let mut ordered_suggestions: Vec<(u32, EmojiPair)> = vec![];
-        for result in search_results {
-            let suggestion: EmojiPair = result.1.to_owned();
-            ordered_suggestions.push((result.0, suggestion));
+    // `handle_key` returns the emojis the user picked once they are done
+    fn handle_key(&mut self, key: KeyEvent) -> Result<Option<Vec<EmojiPair>>, Error> {
+        let binding = KeyBinding::from(key);
+        if let Some(action) = self.keymap.action(self.context(), binding) {
+            return self.perform(action);
         }
 
-        ordered_suggestions.sort_by_key(|k| k.0);
-        let mut new_suggestions: Vec<EmojiPair> = vec![];
-        for suggestion in ordered_suggestions {
-            new_suggestions.push(suggestion.1);
+        // Printable characters that aren't bound to an action are typed into
+        // the search box, moving the focus back to it if necessary
+        if let KeyCode::Char(new_char) = binding.code {
+            if binding.modifiers.is_empty() {
+                self.edit(|input| input.insert_char(new_char));
+            }
         }
 
-        // step #3: filter out anything that doesn't almost match
-        self.items = new_suggestions
-            .iter()
-            .enumerate()
-            .filter(|&(_, v)| v.description.starts_with(item.as_str()))
-            .map(|(_, e)| e.to_owned())
-            .collect();
+        Ok(None)
+    }
 
-        // step #4: save the first 5 results
-        self.items = self
-            .items
-            .iter()
-            .enumerate()
-            .filter(|&(i, _)| i < 5)
-            .map(|(_, e)| e.to_owned())
-            .collect();
+    fn handle_paste(&mut self, pasted: &str) {
+        self.edit(|input| input.insert_str(pasted));
     }
 
-    fn move_cursor_left(&mut self) {
-        let cursor_moved_left = self.cursor_position.saturating_sub(1);
-        self.cursor_position = self.clamp_cursor(cursor_moved_left);
+    // `edit` applies a change to the search box, giving it the focus and
+    // updating the suggestions if the search term changed
+    fn edit(&mut self, change: impl FnOnce(&mut LineInput)) {
+        self.mode = InputMode::Searching;
+
+        let before = self.input.text().to_owned();
+        change(&mut self.input);
+
+        if self.input.text() != before {
+            self.refresh();
+        }
     }
 
-    fn move_cursor_right(&mut self) {
-        let cursor_moved_right = self.cursor_position.saturating_add(1);
-        self.cursor_position = self.clamp_cursor(cursor_moved_right);
+    // `basket` is the text that copying would produce for the marked emojis
+    fn basket(&self) -> String {
+        self.join(&self.marked)
     }
 
-    fn enter_char(&mut self, new_char: char) {
-        self.user_input.insert(self.cursor_position, new_char);
-        self.move_cursor_right();
+    fn join(&self, pairs: &[EmojiPair]) -> String {
+        pairs
+            .iter()
+            .map(|pair| pair.with_skin_tone(self.skin_tone))
+            .collect::<Vec<&str>>()
+            .join(&self.separator)
     }
 
-    fn delete_char(&mut self) {
-        let is_not_cursor_leftmost = self.cursor_position != 0;
-        if is_not_cursor_leftmost {
-            // Method "remove" is not used on the saved text for deleting the selected char.
-            // Reason: Using remove on String works on bytes instead of the chars.
-            // Using remove would require special care because of char boundaries.
-            let current_index = self.cursor_position;
-            let from_left_to_current_index = current_index - 1;
+    // `selection` is what copying copies: the marked emojis if there are
+    // any, otherwise the highlighted one
+    fn selection(&self) -> Option<Vec<EmojiPair>> {
+        if self.marked.is_empty() {
+            self.carousel.select().cloned().map(|pair| vec![pair])
+        } else {
+            Some(self.marked.clone())
+        }
+    }
+
+    // `copy_and_stay` adds the selection to everything copied so far and
+    // puts the lot on the clipboard without leaving the picker
+    fn copy_and_stay(&mut self) {
+        let Some(selection) = self.selection() else {
+            return;
+        };
 
-            // Getting all characters before the selected character
-            let before_char_to_delete = self.user_input.chars().take(from_left_to_current_index);
-            // Getting all characters after the selected character
-            let after_char_to_delete = self.user_input.chars().skip(current_index);
+        // Failing to save the history shouldn't stop the emoji from being
+        // copied
+        let _ = self.input.remember();
 
-            // Put all characters together except the selected one.
-            // By leaving the selected one out, it is forgotten and therefore deleted.
-            self.user_input = before_char_to_delete.chain(after_char_to_delete).collect();
-            self.move_cursor_left();
+        let status = format!("copied {}", self.join(&selection));
+        self.copied.extend(selection);
+        self.marked.clear();
+
+        let status = if matches!(self.output, OutputMode::Clipboard | OutputMode::Both) {
+            match clipboard::copy(&self.join(&self.copied), &self.clipboard) {
+                Ok(()) => status,
+                Err(error) => format!("copy failed: {}", error),
+            }
+        } else {
+            status
+        };
+
+        self.status = Some((status, Instant::now()));
+    }
+
+    // `status` is the message to show the user, if it hasn't expired yet
+    fn status(&self) -> Option<&str> {
+        let (message, shown) = self.status.as_ref()?;
+        if shown.elapsed() < Duration::from_millis(STATUS_DURATION_MS) {
+            Some(message)
+        } else {
+            None
         }
     }
 
-    fn clamp_cursor(&self, new_cursor_pos: usize) -> usize {
-        new_cursor_pos.clamp(0, self.user_input.len())
+    // `hint` tells the user which keys move through the suggestions, or
+    // pick the highlighted one, and how to get help. It follows the key
+    // bindings in effect and leaves out actions no key is bound to
+    fn hint(&self) -> String {
+        let context = self.context();
+        let picks = match self.output {
+            OutputMode::Stdout => "print emoji",
+            OutputMode::Clipboard | OutputMode::Both => "copy emoji",
+        };
+        let moves = match self.mode {
+            InputMode::Searching => (vec![Action::SelectNext, Action::SelectPrev], "select emoji"),
+            InputMode::Selecting => (vec![Action::Copy], picks),
+        };
+
+        let mut parts = Vec::new();
+        for (actions, what) in [moves, (vec![Action::ShowHelp], "help")] {
+            // The first key of each action is enough for a hint
+            let keys: Vec<String> = actions
+                .into_iter()
+                .filter_map(|action| self.keymap.keys(context, action).first().copied())
+                .map(|key| key.to_string())
+                .collect();
+            if !keys.is_empty() {
+                parts.push(format!("{}: {}", keys.join("/"), what));
+            }
+        }
+
+        parts.join(" · ")
     }
 
-    fn reset_cursor(&mut self) {
-        self.cursor_position = 0;
+    fn perform(&mut self, action: Action) -> Result<Option<Vec<EmojiPair>>, Error> {
+        match action {
+            Action::SelectNext if self.view == View::Grid => self.move_vertically(true),
+            Action::SelectPrev if self.view == View::Grid => self.move_vertically(false),
+            Action::SelectNext | Action::SelectRight => {
+                self.mode = InputMode::Selecting;
+                self.carousel.next();
+            }
+            Action::SelectPrev | Action::SelectLeft => {
+                self.mode = InputMode::Selecting;
+                self.carousel.previous();
+            }
+            Action::Unselect => {
+                self.mode = InputMode::Searching;
+                self.carousel.unselect();
+            }
+            Action::Copy if !self.keep_open => {
+                let selection = self.selection();
+                if selection.is_some() {
+                    // Failing to save the history shouldn't stop the emoji
+                    // from being copied
+                    let _ = self.input.remember();
+                }
+
+                return Ok(selection);
+            }
+            Action::Copy | Action::CopyAndStay => self.copy_and_stay(),
+            Action::Cancel => {
+                self.carousel.quit();
+
+                // Leaving after copying with the picker kept open isn't a
+                // cancellation; the copied emojis are the result
+                if self.copied.is_empty() {
+                    return Err(Error::UserCanceledProgram);
+                }
+
+                return Ok(Some(std::mem::take(&mut self.copied)));
+            }
+            Action::ToggleFavorite => self.toggle_favorite(),
+            Action::ToggleMark => self.toggle_mark(),
+            Action::ToggleDetails => self.details = !self.details,
+            Action::ToggleView => self.toggle_view(),
+            #[cfg(feature = "regex")]
+            Action::ToggleRegex => {
+                self.regex = !self.regex;
+                self.carousel.set_regex(self.regex);
+                self.refresh();
+            }
+            Action::ShowHelp => self.help = Some(0),
+            Action::ClearQuery => {
+                self.carousel.unselect();
+                self.edit(LineInput::clear);
+            }
+            Action::BackwardDeleteChar => self.edit(LineInput::backward_delete_char),
+            Action::BackwardChar => self.edit(LineInput::backward_char),
+            Action::ForwardChar => self.edit(LineInput::forward_char),
+            Action::BackwardWord => self.edit(LineInput::backward_word),
+            Action::ForwardWord => self.edit(LineInput::forward_word),
+            Action::BeginningOfLine => self.edit(LineInput::beginning_of_line),
+            Action::EndOfLine => self.edit(LineInput::end_of_line),
+            Action::DeleteChar => self.edit(LineInput::delete_char),
+            Action::BackwardKillWord => self.edit(LineInput::backward_kill_word),
+            Action::BackwardKillLine => self.edit(LineInput::backward_kill_line),
+            Action::KillLine => self.edit(LineInput::kill_line),
+            Action::PreviousHistory => self.edit(LineInput::previous_history),
+            Action::NextHistory => self.edit(LineInput::next_history),
+        }
+
+        Ok(None)
     }
 }
 
-pub struct App {
-    items: EmojiSuggestions<EmojiPair>,
+// `EventSource` is where the picker reads its input from, so that it can be
+// driven by something other than the terminal
+pub trait EventSource {
+    // `next` waits up to `timeout` for an event, returning `None` if none
+    // arrived in time
+    fn next(&mut self, timeout: Duration) -> Result<Option<Event>, Error>;
 }
 
-impl App {
-    pub fn new() -> Self {
-        App {
-            items: EmojiSuggestions::new(),
+// `TerminalEvents` reads key presses, mouse clicks, pastes and resizes from
+// the terminal
+pub struct TerminalEvents;
+
+impl EventSource for TerminalEvents {
+    fn next(&mut self, timeout: Duration) -> Result<Option<Event>, Error> {
+        if event::poll(timeout).map_err(Error::Terminal)? {
+            event::read().map(Some).map_err(Error::Terminal)
+        } else {
+            Ok(None)
         }
     }
 }
@@ -212,117 +766,49 @@ impl App {
 pub fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
     mut app: App,
+    events: &mut impl EventSource,
+    probe: &mut impl Probe,
     tick_rate: Duration,
-) -> Result<EmojiPair, Box<dyn Error>> {
+) -> Result<Vec<EmojiPair>, Error> {
     let mut last_tick = Instant::now();
 
-    app.items.items.push(EmojiPair {
-        description: String::from("crab"),
-        emoji: String::from("🦀"),
-    });
-    app.items.items.push(EmojiPair {
-        description: String::from("snake"),
-        emoji: String::from("🐍"),
-    });
-    app.items.items.push(EmojiPair {
-        description: String::from("monkey"),
-        emoji: String::from("🐒"),
-    });
-    app.items.items.push(EmojiPair {
-        description: String::from("eyeglasses"),
-        emoji: String::from("👓"),
-    });
-    app.items.items.push(EmojiPair {
-        description: String::from("coffee"),
-        emoji: String::from("☕"),
-    });
-
     loop {
-        terminal.draw(|f| ui(f, &mut app))?;
+        let frame = terminal
+            .draw(|f| ui(f, &mut app))
+            .map_err(Error::Terminal)?;
+
+        // Emoji sequences shown for the first time are checked before
+        // waiting for input, and the frame drawn again with those the
+        // terminal can't draw replaced
+        let unchecked: Vec<String> = app
+            .render_check
+            .unchecked(app.visible_glyphs())
+            .into_iter()
+            .map(String::from)
+            .collect();
+        if !unchecked.is_empty() {
+            let drawn = frame.buffer.clone();
+            check_rendering(terminal, &mut app, probe, &unchecked, &drawn)?;
+            continue;
+        }
 
         let timeout = tick_rate
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
-        if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match app.items.mode {
-                        InputMode::Searching => match key.code {
-                            KeyCode::Up => {
-                                app.items.mode = InputMode::Selecting;
-                                app.items.previous();
-                            }
-                            KeyCode::Down => {
-                                app.items.mode = InputMode::Selecting;
-                                app.items.next();
-                            }
-                            KeyCode::Backspace => {
-                                app.items.delete_char();
-                                let user_input = &app.items.user_input.clone();
-                                app.items.search(user_input);
-
-                                if user_input.is_empty() {
-                                    app.items.items.clear();
-                                }
-                            }
-                            KeyCode::Char('c') => {
-                                if key.modifiers.contains(event::KeyModifiers::CONTROL) {
-                                    return Err(Box::new(EmojiError::UserCanceledProgram));
-                                } else {
-                                    app.items.enter_char('c');
-                                    let user_input = &app.items.user_input.clone();
-                                    app.items.search(user_input);
-                                }
-                            }
-                            KeyCode::Char(new_char) => {
-                                app.items.enter_char(new_char);
-                                let user_input = &app.items.user_input.clone();
-                                app.items.search(user_input);
-                            }
-                            _ => {}
-                        },
-                        InputMode::Selecting => match key.code {
-                            KeyCode::Left => app.items.unselect(),
-                            KeyCode::Down => app.items.next(),
-                            KeyCode::Up => app.items.previous(),
-                            KeyCode::Enter => match app.items.select() {
-                                Some(selection) => {
-                                    return Ok(selection.clone());
-                                }
-                                _ => {} // If nothing is selected, don't do anything
-                            },
-                            KeyCode::Backspace => {
-                                app.items.delete_char();
-                                let user_input = &app.items.user_input.clone();
-                                app.items.search(user_input);
-                                app.items.mode = InputMode::Searching;
-
-                                if user_input.is_empty() {
-                                    app.items.items.clear();
-                                }
-                            }
-                            KeyCode::Char('c') => {
-                                if key.modifiers.contains(event::KeyModifiers::CONTROL) {
-                                    return Err(Box::new(EmojiError::UserCanceledProgram));
-                                } else {
-                                    app.items.enter_char('c');
-                                    let user_input = &app.items.user_input.clone();
-                                    app.items.search(user_input);
-                                    app.items.mode = InputMode::Searching;
-                                }
-                            }
-                            KeyCode::Char(new_char) => {
-                                app.items.mode = InputMode::Searching;
-                                app.items.enter_char(new_char);
-
-                                let user_input = &app.items.user_input.clone();
-                                app.items.search(user_input);
-                            }
-                            _ => {}
-                        },
-                    }
+        match events.next(timeout)? {
+            // The layout is worked out again for the new size on the next
+            // frame
+            Some(Event::Resize(..)) => terminal.autoresize().map_err(Error::Terminal)?,
+
+            // Whatever was drawn over the picker while the terminal was in
+            // the background is painted over on the next frame
+            Some(Event::FocusGained) => terminal.clear().map_err(Error::Terminal)?,
+            Some(event) => {
+                if let Some(selection) = app.handle_event(event)? {
+                    return Ok(selection);
                 }
             }
+            None => {}
         }
 
         if last_tick.elapsed() >= tick_rate {
@@ -331,10 +817,45 @@ pub fn run_app<B: Backend>(
     }
 }
 
+// `check_rendering` probes each of `glyphs` on the top row of the picker,
+// then puts back what `drawn` has on that row
+fn check_rendering<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    probe: &mut impl Probe,
+    glyphs: &[String],
+    drawn: &Buffer,
+) -> Result<(), Error> {
+    let area = drawn.area;
+    terminal.hide_cursor().map_err(Error::Terminal)?;
+    for glyph in glyphs {
+        app.render_check.check(probe, glyph, area.x, area.y);
+    }
+
+    // Like ratatui does, the cells covered by a wide character are skipped
+    let mut row = Vec::new();
+    let mut skip = 0;
+    for x in area.left()..area.right() {
+        let cell = drawn.get(x, area.y);
+        if skip > 0 {
+            skip -= 1;
+            continue;
+        }
+
+        skip = cell.symbol.width().saturating_sub(1);
+        row.push((x, area.y, cell));
+    }
+
+    let backend = terminal.backend_mut();
+    backend.draw(row.into_iter()).map_err(Error::Terminal)?;
+    backend.flush().map_err(Error::Terminal)
+}
+
 fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     // Create two chunks, the top chunk for getting user input,
     // the bottom chunk for displaying suggestions that the user
-    // can choose from:
+    // can choose from. Once the user has marked emojis, a third
+    // chunk at the bottom shows what will be copied:
     //
     // __Input_________________________________________________
     // |                                                      |
@@ -347,69 +868,1185 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
     // | 3. coffee    ☕                                       |
     // | ...                                                  |
     // |______________________________________________________|
+    //
+    // On wide terminals the suggestions share their chunk with a pane on
+    // the right showing the details of the highlighted emoji
+    let basket = app.basket();
+    let constraints = if app.marked.is_empty() {
+        vec![Constraint::Length(3), Constraint::Min(3)]
+    } else {
+        vec![
+            Constraint::Length(3),
+            Constraint::Min(3),
+            Constraint::Length(3),
+        ]
+    };
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(3), Constraint::Min(3)].as_ref())
+        .constraints(constraints)
         .split(f.size());
+    app.input_area = chunks[0];
+
+    let (list_area, details_area) = if app.details && f.size().width >= DETAILS_MIN_WIDTH {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(DETAILS_WIDTH)])
+            .split(chunks[1]);
+        (columns[0], Some(columns[1]))
+    } else {
+        (chunks[1], None)
+    };
+    app.suggestions_area = list_area;
+
+    // A search term too long for the search box is scrolled sideways to
+    // keep the cursor inside it
+    let cursor_column = app.input.cursor_column() as u16;
+    let scroll = cursor_column.saturating_sub(chunks[0].width.saturating_sub(3));
+
+    // The title says when the search term is a regular expression
+    #[cfg(feature = "regex")]
+    let title = if app.regex {
+        format!("{} [regex]", app.prompt)
+    } else {
+        app.prompt.clone()
+    };
+    #[cfg(not(feature = "regex"))]
+    let title = app.prompt.clone();
+
+    // Create the input widget for searches
+    let input = Paragraph::new(app.input.text())
+        .scroll((0, scroll))
+        .style(match app.mode {
+            InputMode::Searching => app.theme.input,
+            InputMode::Selecting => Style::default(),
+        })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(app.theme.borders)
+                .title(title),
+        );
+
+    // We can now render the search bar
+    f.render_widget(input, chunks[0]);
+
+    // A search term that isn't a valid query is explained on the bottom
+    // border of the search box
+    if let Some(error) = &app.query_error {
+        let area = Rect::new(
+            chunks[0].x + 1,
+            chunks[0].bottom().saturating_sub(1),
+            chunks[0].width.saturating_sub(2),
+            1,
+        );
+        let error = Paragraph::new(Span::styled(error.clone(), app.theme.error));
+        f.render_widget(error, area);
+    }
+
+    match app.mode {
+        InputMode::Searching => {
+            f.set_cursor(chunks[0].x + cursor_column - scroll + 1, chunks[0].y + 1)
+        }
+        InputMode::Selecting => {}
+    }
+
+    if app.view == View::Grid {
+        let emojis = emoji_grid(app, list_area);
+        f.render_widget(emojis, list_area);
+    } else {
+        render_list(f, app, list_area);
+    }
+
+    if let Some(area) = details_area {
+        f.render_widget(details(app), area);
+    }
+
+    if let Some(area) = chunks.get(2) {
+        let marked = Paragraph::new(basket.as_str()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(app.theme.borders)
+                .title(format!("Marked ({})", app.marked.len())),
+        );
+
+        f.render_widget(marked, *area);
+    }
+
+    if app.help.is_some() {
+        render_help(f, app);
+    }
+
+    f.render_widget(WideGlyphs, f.size());
+}
+
+fn contains(area: Rect, x: u16, y: u16) -> bool {
+    (area.left()..area.right()).contains(&x) && (area.top()..area.bottom()).contains(&y)
+}
 
-    // If the user input has changed, update the list
-    if app.items.user_input_changed {
-        // Create the input widget for searches
-        let input = Paragraph::new(app.items.user_input.as_str())
-            .style(match app.items.mode {
-                InputMode::Searching => Style::default().fg(Color::Yellow),
-                InputMode::Selecting => Style::default(),
+// `WideGlyphs` makes up for emojis that ratatui measures narrower than
+// terminals draw them, such as 🖍️, which is a narrow crayon followed by a
+// variation selector. The cell after such an emoji is emptied, since the
+// emoji covers it, so that the rest of its line isn't pushed to the right
+struct WideGlyphs;
+
+impl Widget for WideGlyphs {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right().saturating_sub(1) {
+                let symbol = &buf.get(x, y).symbol;
+                if width::display_width(symbol) > symbol.width() {
+                    buf.get_mut(x + 1, y).set_symbol("");
+                }
+            }
+        }
+    }
+}
+
+fn render_list<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
+    let msg = app.status().map(String::from).unwrap_or_else(|| app.hint());
+
+    // Create the list widget that will be used to display suggestions
+    let items: Vec<ListItem> = app
+        .carousel
+        .items()
+        .iter()
+        .map(|i| {
+            let i = app.render_check.shown(i);
+            let mut suggestion = if app.favorites.contains(&i.description) {
+                format!("{:width$} ★", i, width = app.name_width)
+            } else {
+                format!("{:width$}", i, width = app.name_width)
+            };
+
+            // Only make room for the marker once something is marked
+            if app.is_marked(&i.description) {
+                suggestion.insert_str(0, "✓ ");
+            } else if !app.marked.is_empty() {
+                suggestion.insert_str(0, "  ");
+            }
+
+            ListItem::new(Line::from(suggestion))
+        })
+        .collect();
+
+    let items = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(app.theme.borders)
+                .title(Span::styled(msg, app.theme.status))
+                .title_on_bottom(),
+        )
+        .style(match app.mode {
+            InputMode::Selecting => app.theme.list,
+            InputMode::Searching => Style::default(),
+        })
+        .highlight_style(app.theme.highlight)
+        .highlight_symbol("> ");
+
+    // We can now render the emoji suggestions
+    app.list.select(app.carousel.position());
+    f.render_stateful_widget(items, area, &mut app.list);
+}
+
+// `emoji_grid` lays the suggestions out in rows of glyphs under category
+// headers, scrolled so that the highlighted emoji is visible. A line of
+// tabs at the top shows the first emoji of each category, with the current
+// one in brackets. The name of the highlighted emoji is shown at the bottom
+fn emoji_grid(app: &mut App, area: Rect) -> Paragraph<'static> {
+    app.grid_columns = (area.width.saturating_sub(2) / GRID_CELL_WIDTH).max(1) as usize;
+    let grid = Grid::new(app.carousel.items(), app.grid_columns);
+
+    // Scroll just far enough to show the highlighted row, along with the
+    // header above it when that row starts a category
+    let height = area.height.saturating_sub(3) as usize;
+    app.grid_height = height;
+    if let Some(row) = app.carousel.position().and_then(|i| grid.row_of(i)) {
+        let first = match row.checked_sub(1).map(|above| &grid.rows[above]) {
+            Some(Row::Header(_)) => row - 1,
+            _ => row,
+        };
+
+        if first < app.grid_offset {
+            app.grid_offset = first;
+        } else if row >= app.grid_offset + height {
+            app.grid_offset = row + 1 - height;
+        }
+    }
+    app.grid_offset = app.grid_offset.min(grid.rows.len().saturating_sub(height));
+
+    let items = app.carousel.items();
+    let categories = grid.categories();
+
+    // The current category is the highlighted emoji's, or that of the first
+    // emoji shown while none is highlighted
+    let current = app.carousel.position().or_else(|| {
+        grid.rows[app.grid_offset.min(grid.rows.len())..]
+            .iter()
+            .find_map(|row| match row {
+                Row::Cells { start, .. } => Some(*start),
+                Row::Header(_) => None,
             })
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(crate::constants::SEARCH_PROMPT),
-            );
-
-        // We can now render the search bar
-        f.render_widget(input, chunks[0]);
-
-        match app.items.mode {
-            InputMode::Searching => f.set_cursor(
-                chunks[1].x + app.items.cursor_position as u16 + 1,
-                chunks[0].y + 1,
+    });
+    let current = current.and_then(|index| categories.iter().rposition(|start| *start <= index));
+
+    let tabs = Line::from(
+        categories
+            .iter()
+            .enumerate()
+            .map(|(category, start)| {
+                let glyph = app.grid_glyph(&items[*start]);
+                let (open, close) = if current == Some(category) {
+                    ("[", "]")
+                } else {
+                    (" ", " ")
+                };
+
+                // ratatui measures some emojis narrower than they are drawn,
+                // so the cell they cover is filled for `WideGlyphs` to empty
+                // rather than the closing bracket
+                let glyph_width = width::display_width(&glyph);
+                let cover = " ".repeat(glyph_width.saturating_sub(glyph.width()));
+                let padding =
+                    " ".repeat((GRID_CELL_WIDTH as usize - 2).saturating_sub(glyph_width));
+
+                Span::raw(format!("{}{}{}{}{}", open, glyph, cover, close, padding))
+            })
+            .collect::<Vec<Span>>(),
+    );
+
+    let rows = grid
+        .rows
+        .iter()
+        .skip(app.grid_offset)
+        .take(height)
+        .map(|row| match row {
+            Row::Header(category) => Line::from(Span::styled(
+                category.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Row::Cells { start, end } => Line::from(
+                (*start..*end)
+                    .map(|index| {
+                        let pair = &items[index];
+                        let glyph = app.grid_glyph(pair);
+                        let padding = (GRID_CELL_WIDTH as usize - 1)
+                            .saturating_sub(width::display_width(&glyph));
+
+                        let mut style = Style::default();
+                        if app.is_marked(&pair.description) {
+                            style = style.add_modifier(Modifier::UNDERLINED);
+                        }
+                        if app.carousel.position() == Some(index) {
+                            style = style.patch(app.grid_highlight());
+                        }
+
+                        Span::styled(format!(" {}{}", glyph, " ".repeat(padding)), style)
+                    })
+                    .collect::<Vec<Span>>(),
             ),
-            InputMode::Selecting => {}
+        });
+    let lines: Vec<Line> = std::iter::once(tabs).chain(rows).collect();
+
+    let status = match (app.status(), app.carousel.select()) {
+        (Some(status), _) => String::from(status),
+        (None, Some(pair)) => {
+            let glyph = pair.with_skin_tone(app.skin_tone);
+            if app.render_check.renders(glyph) {
+                pair.description.clone()
+            } else {
+                format!("{} ({})", pair.description, pair::codepoints(glyph))
+            }
+        }
+        (None, None) => app.hint(),
+    };
+
+    Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(app.theme.borders)
+                .title(Span::styled(status, app.theme.status))
+                .title_on_bottom(),
+        )
+        .style(match app.mode {
+            InputMode::Selecting => app.theme.list,
+            InputMode::Searching => Style::default(),
+        })
+}
+
+// `render_help` draws the help overlay in the middle of the picker, scrolled
+// no further than its last line
+fn render_help<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let size = f.size();
+    let width = size.width.min(HELP_WIDTH);
+    let height = size.height.saturating_sub(2).max(size.height.min(3));
+    let area = Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+
+    let lines = help(&app.keymap, width.saturating_sub(2) as usize);
+    let shown = height.saturating_sub(2) as usize;
+    let scroll = app.help.unwrap_or(0).min(lines.len().saturating_sub(shown));
+    app.help = Some(scroll);
+
+    let help = Paragraph::new(lines).scroll((scroll as u16, 0)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(app.theme.borders)
+            .title("Help (Esc to close)"),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(help, area);
+}
+
+// `help` explains how to search and what each part of the picker is for,
+// followed by every key binding in effect. Descriptions too long for `width`
+// columns continue on the next line, beneath where they started
+fn help(keymap: &KeyMap, width: usize) -> Vec<Line<'static>> {
+    let heading = |text: &str| {
+        Line::from(Span::styled(
+            text.to_owned(),
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+    };
+
+    let mut lines = vec![
+        heading("Searching"),
+        Line::from("  Type part of a name to see the emojis with a word starting with"),
+        Line::from("  it, closest matches first: `cra` suggests crab and rice_cracker."),
+        Line::from("  Every term of the search has to match:"),
+        Line::from(""),
+        Line::from("  \"thumbs up\"    These words in order, in a name, alias or keyword"),
+        Line::from("  ^cra  face$    The name starts or ends with this; ^crab$ is exact"),
+        Line::from("  cat:food       The category contains this"),
+        Line::from("  kw:party       A keyword starts with this"),
+        Line::from("  v:<=13         Introduced in Emoji 13.0 or earlier"),
+        Line::from("  tone:yes       Comes in different skin tones"),
+        Line::from("  !term          Anything the term doesn't match"),
+        Line::from(""),
+    ];
+
+    #[cfg(feature = "regex")]
+    lines.extend([
+        Line::from("  In regex mode the search term is a regular expression matched"),
+        Line::from("  against every name and alias instead: ^(cat|dog) lists both."),
+        Line::from(""),
+    ]);
+
+    lines.extend([
+        Line::from("  With nothing typed, the list suggests your favorites and the"),
+        Line::from("  grid shows every emoji."),
+        Line::from(""),
+        heading("Modes"),
+        Line::from("  Search box   Keys are typed into the search term"),
+        Line::from("  List         A suggestion is highlighted and can be copied"),
+        Line::from("  Grid         Emojis are grouped by category, with a tab for each"),
+        Line::from(""),
+        heading("Mouse"),
+        Line::from("  Click a suggestion to highlight it and double-click to copy it."),
+        Line::from("  The wheel moves through the suggestions."),
+    ]);
+
+    let mut current = None;
+    for (context, action, keys) in keymap.grouped() {
+        if current != Some(context) {
+            lines.push(Line::from(""));
+            lines.push(heading(context.description()));
+            current = Some(context);
+        }
+
+        let keys: Vec<String> = keys.iter().map(KeyBinding::to_string).collect();
+        let mut line = format!("  {:<20}", keys.join(", "));
+        let indent = line.len() + 1;
+        for word in action.description().split(' ') {
+            if line.len() > indent && line.len() + 1 + word.len() > width {
+                lines.push(Line::from(std::mem::replace(
+                    &mut line,
+                    " ".repeat(indent - 1),
+                )));
+            }
+            line.push(' ');
+            line.push_str(word);
+        }
+        lines.push(Line::from(line));
+    }
+
+    lines
+}
+
+// `details` describes the highlighted emoji: the glyph on its own, followed
+// by everything else known about it
+fn details(app: &App) -> Paragraph<'static> {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(app.theme.borders)
+        .title("Details");
+
+    let Some(pair) = app.carousel.select() else {
+        return Paragraph::new("Highlight a suggestion to see its details")
+            .block(block)
+            .wrap(Wrap { trim: true });
+    };
+
+    let field = |name: &str, value: String| {
+        Line::from(vec![
+            Span::styled(
+                format!("{:<11}", name),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(value),
+        ])
+    };
+
+    let or_none = |values: &[String], separator: &str| {
+        if values.is_empty() {
+            String::from("none")
+        } else {
+            values.join(separator)
+        }
+    };
+
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            app.detail_glyph(pair),
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+        .alignment(Alignment::Center),
+        Line::from(""),
+        field("Name", pair.description.clone()),
+        field("Codepoints", pair.codepoints()),
+        field("Aliases", or_none(&pair.aliases, ", ")),
+        field("Category", pair.category.clone()),
+        field("Version", format!("Emoji {}", pair.version)),
+        field("Keywords", or_none(&pair.keywords, ", ")),
+        field("Skin tones", or_none(&pair.skin_tones, " ")),
+    ];
+
+    Paragraph::new(lines).block(block).wrap(Wrap { trim: true })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::time::Duration;
+
+    use crossterm::event::{
+        Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    };
+    use ratatui::backend::{Backend, TestBackend};
+    use ratatui::buffer::Cell;
+    use ratatui::style::{Color, Modifier};
+    use ratatui::Terminal;
+
+    use crate::carousel::Carousel;
+    use crate::config::{Config, OutputMode};
+    use crate::error::Error;
+    use crate::favorites::Favorites;
+    use crate::grid::Grid;
+    use crate::history::History;
+    use crate::pair::EmojiPair;
+    use crate::render::Probe;
+    use crate::search::{emoji_list, emoji_tree};
+    use crate::theme::{ColorMode, ThemeConfig, ThemeError, ThemeName};
+    use crate::ui::{run_app, ui, App, EventSource, InputMode};
+    use crate::width;
+
+    // `Script` replays a fixed sequence of events, failing once it runs out
+    // so that a picker which never finishes doesn't hang the test
+    struct Script(VecDeque<Event>);
+
+    impl EventSource for Script {
+        fn next(&mut self, _timeout: Duration) -> Result<Option<Event>, Error> {
+            match self.0.pop_front() {
+                Some(event) => Ok(Some(event)),
+                None => Err(Error::Terminal(std::io::ErrorKind::UnexpectedEof.into())),
+            }
+        }
+    }
+
+    // `Pieces` is a terminal that draws the listed emojis as several, and
+    // every other one as a single emoji
+    struct Pieces(&'static [&'static str]);
+
+    impl Probe for Pieces {
+        fn measure(&mut self, glyph: &str, _x: u16, _y: u16) -> Result<u16, Error> {
+            Ok(if self.0.contains(&glyph) { 4 } else { 2 })
+        }
+    }
+
+    // `Harness` drives the picker one event at a time and draws it on a
+    // `TestBackend` after each one
+    struct Harness {
+        app: App,
+        terminal: Terminal<TestBackend>,
+    }
+
+    impl Harness {
+        fn new(width: u16, height: u16) -> Harness {
+            Harness::with_config(&Config::default(), width, height)
+        }
+
+        fn with_config(config: &Config, width: u16, height: u16) -> Harness {
+            let app = Harness::app(config).unwrap();
+
+            let mut harness = Harness {
+                app,
+                terminal: Terminal::new(TestBackend::new(width, height)).unwrap(),
+            };
+            harness.draw();
+            harness
+        }
+
+        // `app` builds the picker the harness drives from `config`, with the
+        // embedded emojis and no history or favorites
+        fn app(config: &Config) -> Result<App, Error> {
+            App::with(
+                config,
+                emoji_tree().unwrap(),
+                &emoji_list().unwrap(),
+                History::default(),
+                Favorites::default(),
+            )
+        }
+
+        fn draw(&mut self) {
+            let app = &mut self.app;
+            self.terminal.draw(|f| ui(f, app)).unwrap();
+        }
+
+        // `send` hands the picker an event, returning what it finished with
+        // if the event ended it
+        fn send(&mut self, event: Event) -> Option<Result<Vec<EmojiPair>, Error>> {
+            let done = self.app.handle_event(event).transpose();
+            self.draw();
+            done
+        }
+
+        // `press` sends each key in turn, returning what the picker finished
+        // with if one of them ended it
+        fn press(&mut self, keys: &[KeyEvent]) -> Option<Result<Vec<EmojiPair>, Error>> {
+            for key in keys {
+                let done = self.send(Event::Key(*key));
+                if done.is_some() {
+                    return done;
+                }
+            }
+
+            None
+        }
+
+        fn click(&mut self, x: u16, y: u16) -> Option<Result<Vec<EmojiPair>, Error>> {
+            self.send(mouse(MouseEventKind::Down(MouseButton::Left), x, y))
+        }
+
+        fn type_text(&mut self, text: &str) {
+            let keys: Vec<KeyEvent> = text.chars().map(|c| key(KeyCode::Char(c))).collect();
+            assert!(self.press(&keys).is_none());
+        }
+
+        // `resize` changes the size of the terminal, like dragging the
+        // corner of its window does, and draws the picker again
+        fn resize(&mut self, width: u16, height: u16) {
+            self.terminal.backend_mut().resize(width, height);
+            self.draw();
+        }
+
+        fn snapshot(&self) -> String {
+            snapshot(&self.terminal)
+        }
+
+        fn cell(&self, x: u16, y: u16) -> Cell {
+            self.terminal.backend().buffer().get(x, y).clone()
         }
 
-        let msg = match app.items.mode {
-            InputMode::Searching => String::from("⬆️  ⬇️  [Up / Down Arrows]: Select emoji"),
-            InputMode::Selecting => {
-                String::from("↩️   [Enter / Return Key]: Copy emoji to clipboard")
+        fn cursor(&mut self) -> (u16, u16) {
+            self.terminal.backend_mut().get_cursor().unwrap()
+        }
+    }
+
+    // `snapshot` is the text on screen, one line per row with trailing
+    // spaces removed. The cells covered by a wide character are skipped,
+    // measured the way a terminal draws them
+    fn snapshot(terminal: &Terminal<TestBackend>) -> String {
+        let buffer = terminal.backend().buffer();
+        let mut lines = Vec::new();
+
+        for y in 0..buffer.area.height {
+            let mut line = String::new();
+            let mut skip = 0;
+            for x in 0..buffer.area.width {
+                if skip > 0 {
+                    skip -= 1;
+                    continue;
+                }
+
+                let symbol = &buffer.get(x, y).symbol;
+                skip = width::display_width(symbol).saturating_sub(1);
+                line.push_str(symbol);
             }
+            lines.push(line.trim_end().to_owned());
+        }
+
+        lines.join("\n")
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn mouse(kind: MouseEventKind, x: u16, y: u16) -> Event {
+        Event::Mouse(MouseEvent {
+            kind,
+            column: x,
+            row: y,
+            modifiers: KeyModifiers::NONE,
+        })
+    }
+
+    fn names(pairs: &[EmojiPair]) -> Vec<&str> {
+        pairs.iter().map(|pair| pair.description.as_str()).collect()
+    }
+
+    #[test]
+    fn test_typing() {
+        let mut harness = Harness::new(40, 10);
+        harness.type_text("cra");
+
+        assert_eq!(
+            [
+                "┌Emoji you are searching for 🔍:───────┐",
+                "│cra                                   │",
+                "└──────────────────────────────────────┘",
+                "┌──────────────────────────────────────┐",
+                "│crab                🦀                │",
+                "│crayon              🖍️                │",
+                "│rice_cracker        🍘                │",
+                "│                                      │",
+                "│                                      │",
+                "└down/up: select emoji · f1: help──────┘",
+            ]
+            .join("\n"),
+            harness.snapshot()
+        );
+
+        // the cursor follows the search term inside the input box
+        assert_eq!((4, 1), harness.cursor());
+        harness.press(&[key(KeyCode::Backspace), key(KeyCode::Left)]);
+        assert_eq!((2, 1), harness.cursor());
+    }
+
+    #[test]
+    fn test_query() {
+        let mut harness = Harness::new(60, 10);
+        harness.type_text("cra !^rice");
+        assert_eq!(vec!["crab", "crayon"], names(harness.app.carousel.items()));
+
+        // a query that doesn't parse is explained beneath it
+        harness.type_text(" v:");
+        let snapshot = harness.snapshot();
+        assert!(
+            snapshot.contains("└`v:` has to be followed by a value─"),
+            "{}",
+            snapshot
+        );
+        assert!(harness.app.carousel.items().is_empty());
+
+        harness.type_text("<=1");
+        assert!(!harness.snapshot().contains("has to be followed"));
+        assert_eq!(vec!["crab", "crayon"], names(harness.app.carousel.items()));
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn test_regex() {
+        let mut harness = Harness::new(60, 10);
+        let ctrl_r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        harness.press(&[ctrl_r]);
+        harness.type_text("^cra");
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("[regex]"), "{}", snapshot);
+        assert_eq!(vec!["crab", "crayon"], names(harness.app.carousel.items()));
+
+        // a pattern that doesn't compile is explained beneath it
+        harness.type_text("(");
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("└unclosed group─"), "{}", snapshot);
+        assert!(harness.app.carousel.items().is_empty());
+
+        // switching back reads the search term as a query again
+        harness.press(&[key(KeyCode::Backspace), ctrl_r]);
+        assert!(!harness.snapshot().contains("[regex]"));
+        assert_eq!(vec!["crab", "crayon"], names(harness.app.carousel.items()));
+    }
+
+    #[test]
+    fn test_name_width() {
+        let config = Config {
+            name_width: 5,
+            ..Default::default()
         };
+        let mut harness = Harness::with_config(&config, 40, 10);
+        harness.type_text("cra");
+
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("│crab 🦀"), "{}", snapshot);
+        assert!(snapshot.contains("│cra… 🖍️"), "{}", snapshot);
+    }
 
-        // Create the list widget that will be used to display suggestions
-        let items: Vec<ListItem> = app
-            .items
-            .items
+    #[test]
+    fn test_scrolling() {
+        let mut harness = Harness::new(40, 8);
+        harness.type_text("c");
+        let suggestions: Vec<String> = harness
+            .app
+            .carousel
+            .items()
             .iter()
-            .map(|i| {
-                let suggestion = format!("{}", i);
-                ListItem::new(Line::from(suggestion)).style(Style::default())
-            })
+            .map(|pair| pair.description.clone())
             .collect();
+        assert!(suggestions.len() > 3, "more suggestions than rows");
 
-        let items = List::new(items)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(msg.as_str())
-                    .title_on_bottom(),
-            )
-            .style(match app.items.mode {
-                InputMode::Selecting => Style::default().fg(Color::Yellow),
-                InputMode::Searching => Style::default(),
+        // wrapping around to the last suggestion scrolls it into view
+        harness.press(&[key(KeyCode::Up)]);
+        let last = format!("│> {} ", suggestions.last().unwrap());
+        assert!(harness.snapshot().contains(&last), "{}", harness.snapshot());
+        assert!(!harness
+            .snapshot()
+            .contains(&format!("│  {} ", suggestions[0])));
+
+        // and back to the top
+        harness.press(&[key(KeyCode::Down)]);
+        let first = format!("│> {} ", suggestions[0]);
+        assert!(
+            harness.snapshot().contains(&first),
+            "{}",
+            harness.snapshot()
+        );
+    }
+
+    #[test]
+    fn test_resizing() {
+        let mut harness = Harness::new(80, 14);
+        harness.type_text("laughing");
+        harness.press(&[key(KeyCode::Down)]);
+        assert!(harness.snapshot().contains("┌Details"));
+
+        // the detail pane is left out once the terminal is too narrow, and
+        // comes back when it is widened again
+        harness.resize(60, 14);
+        let snapshot = harness.snapshot();
+        assert!(!snapshot.contains("Details"), "{}", snapshot);
+        assert!(snapshot.contains("│> laughing "), "{}", snapshot);
+        assert!(snapshot
+            .lines()
+            .all(|line| width::display_width(line) <= 60));
+        harness.resize(80, 14);
+        assert!(harness.snapshot().contains("┌Details"));
+
+        // the highlighted suggestion stays visible when rows are taken away
+        let mut harness = Harness::new(40, 12);
+        harness.type_text("c");
+        harness.press(&[key(KeyCode::Up)]);
+        let last = harness.app.carousel.select().unwrap().description.clone();
+        harness.resize(40, 7);
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains(&format!("│> {} ", last)), "{}", snapshot);
+
+        // and so does the highlighted emoji of the grid, whose rows are laid
+        // out again for the new width
+        let mut harness = Harness::new(60, 20);
+        harness.press(&[key(KeyCode::F(2))]);
+        harness.press(&[key(KeyCode::Down); 12]);
+        harness.press(&[key(KeyCode::Right); 5]);
+        harness.resize(30, 10);
+        let app = &harness.app;
+        let position = app.carousel.position().unwrap();
+        let row = Grid::new(app.carousel.items(), app.grid_columns)
+            .row_of(position)
+            .unwrap();
+        assert_eq!(7, app.grid_columns);
+        assert!((app.grid_offset..app.grid_offset + app.grid_height).contains(&row));
+    }
+
+    #[test]
+    fn test_long_input() {
+        let mut harness = Harness::new(20, 10);
+        harness.type_text("smiling_face_with_hearts");
+
+        // the end of the search term is shown, with the cursor after it
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("│_face_with_hearts "), "{}", snapshot);
+        assert!(!snapshot.contains("│smiling"), "{}", snapshot);
+        assert_eq!((18, 1), harness.cursor());
+
+        harness.press(&[key(KeyCode::Home)]);
+        assert!(harness.snapshot().contains("│smiling"));
+        assert_eq!((1, 1), harness.cursor());
+    }
+
+    #[test]
+    fn test_selecting() {
+        let mut harness = Harness::new(40, 10);
+        harness.type_text("cra");
+        harness.press(&[key(KeyCode::Down), key(KeyCode::Down)]);
+
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("│  crab "));
+        assert!(snapshot.contains("│> crayon "));
+        assert!(
+            snapshot.contains("└enter: copy emoji · ?: help"),
+            "{}",
+            snapshot
+        );
+
+        let picked = harness.press(&[key(KeyCode::Enter)]).unwrap().unwrap();
+        assert_eq!(vec!["crayon"], names(&picked));
+    }
+
+    #[test]
+    fn test_hint() {
+        // the hint follows the key bindings and what copying does
+        let mut config = Config {
+            output: OutputMode::Stdout,
+            ..Default::default()
+        };
+        config
+            .keybindings
+            .global
+            .insert("enter".into(), "none".into());
+        config.keybindings.global.insert("f1".into(), "none".into());
+        config.keybindings.list.insert("?".into(), "none".into());
+        config.keybindings.list.insert("x".into(), "copy".into());
+        let mut harness = Harness::with_config(&config, 40, 10);
+
+        harness.type_text("cra");
+        harness.press(&[key(KeyCode::Down)]);
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("└x: print emoji──"), "{}", snapshot);
+    }
+
+    #[test]
+    fn test_marking() {
+        let mut harness = Harness::new(40, 12);
+        harness.type_text("cra");
+        harness.press(&[
+            key(KeyCode::Down),
+            key(KeyCode::Tab),
+            key(KeyCode::Down),
+            key(KeyCode::Tab),
+        ]);
+
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("│  ✓ crab "));
+        assert!(snapshot.contains("│> ✓ crayon "));
+        assert!(snapshot.contains("┌Marked (2)"));
+
+        let picked = harness.press(&[key(KeyCode::Enter)]).unwrap().unwrap();
+        assert_eq!(vec!["crab", "crayon"], names(&picked));
+    }
+
+    #[test]
+    fn test_cancelling() {
+        let mut harness = Harness::new(40, 10);
+        harness.type_text("cra");
+
+        let canceled = harness.press(&[key(KeyCode::Esc)]).unwrap();
+        assert!(matches!(canceled, Err(Error::UserCanceledProgram)));
+        assert!(harness.app.carousel.has_quit());
+    }
+
+    #[test]
+    fn test_details() {
+        let mut harness = Harness::new(80, 14);
+        harness.type_text("laughing");
+        assert!(harness
+            .snapshot()
+            .contains("│Highlight a suggestion to see its"));
+
+        harness.press(&[key(KeyCode::Down)]);
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("┌Details"));
+        assert!(snapshot.contains("│Codepoints U+1F606 "));
+        assert!(snapshot.contains("│Aliases    satisfied "));
+        assert!(snapshot.contains("│Category   Smileys & Emotion "));
+        assert!(snapshot.contains("│Version    Emoji 0.6 "));
+        assert!(snapshot.contains("│Keywords   grinning, squinting"));
+
+        // the pane can be hidden and shown again
+        let hidden = harness.press(&[KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL)]);
+        assert!(hidden.is_none());
+        assert!(!harness.snapshot().contains("Details"));
+        harness.press(&[KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL)]);
+        assert!(harness.snapshot().contains("Details"));
+
+        // and is left out when the terminal is too narrow for it
+        let mut harness = Harness::new(60, 14);
+        harness.type_text("laughing");
+        harness.press(&[key(KeyCode::Down)]);
+        assert!(!harness.snapshot().contains("Details"));
+    }
+
+    #[test]
+    fn test_help() {
+        let mut config = Config::default();
+        config
+            .keybindings
+            .global
+            .insert(String::from("ctrl-x"), String::from("copy_and_stay"));
+        let mut harness = Harness::with_config(&config, 80, 60);
+
+        // `?` is typed into the search box, but opens the help from the list
+        harness.type_text("cra?");
+        assert_eq!("cra?", harness.app.input.text());
+        harness.press(&[key(KeyCode::Backspace), key(KeyCode::Down)]);
+        harness.press(&[key(KeyCode::Char('?'))]);
+
+        // the key bindings listed are those in effect
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("┌Help (Esc to close)"), "{}", snapshot);
+        assert!(snapshot.contains("│Searching"));
+        let copy = snapshot
+            .lines()
+            .position(|line| line.contains("ctrl-x, ctrl-y"))
+            .unwrap();
+        let copy: Vec<&str> = snapshot.lines().skip(copy).take(2).collect();
+        assert!(copy[0].contains(" Copy the highlighted emoji and keep the picker │"));
+        assert!(
+            copy[1].contains("│                       open "),
+            "{:?}",
+            copy
+        );
+
+        // keys scroll the help rather than reaching the picker
+        harness.press(&[key(KeyCode::Down), key(KeyCode::Down), key(KeyCode::Up)]);
+        assert_eq!(Some(1), harness.app.help);
+        harness.type_text("b");
+        assert_eq!("cra", harness.app.input.text());
+        assert_eq!(Some(0), harness.app.carousel.position());
+
+        // `Esc` closes the help without leaving the picker
+        assert!(harness.press(&[key(KeyCode::Esc)]).is_none());
+        assert!(!harness.snapshot().contains("Help"));
+
+        // and so does the key that opened it
+        harness.press(&[key(KeyCode::F(1))]);
+        assert!(harness.snapshot().contains("┌Help"));
+        harness.press(&[key(KeyCode::F(1))]);
+        assert_eq!(None, harness.app.help);
+    }
+
+    #[test]
+    fn test_grid() {
+        let mut harness = Harness::new(40, 12);
+        harness.press(&[key(KeyCode::F(2))]);
+
+        // with nothing typed the whole catalog is laid out by category
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("│Smileys & Emotion"), "{}", snapshot);
+        assert!(snapshot.contains("└down/up: select emoji · f1: help"));
+
+        harness.press(&[key(KeyCode::Down)]);
+        let first = harness.app.carousel.position();
+        assert_eq!(Some(0), first);
+
+        // moving right, down and back up keeps to the same column
+        harness.press(&[key(KeyCode::Right), key(KeyCode::Down)]);
+        let below = harness.app.carousel.position().unwrap();
+        assert_eq!(1 + harness.app.grid_columns, below);
+        harness.press(&[key(KeyCode::Up)]);
+        assert_eq!(Some(1), harness.app.carousel.position());
+
+        // the highlighted emoji is named at the bottom
+        let name = harness.app.carousel.select().unwrap().description.clone();
+        assert!(harness.snapshot().contains(&format!("└{}", name)));
+
+        // moving up from the top row goes back to the search box
+        harness.press(&[key(KeyCode::Up)]);
+        assert_eq!(None, harness.app.carousel.position());
+        assert!(matches!(harness.app.mode, InputMode::Searching));
+
+        harness.type_text("crab");
+        harness.press(&[key(KeyCode::Down)]);
+        assert!(harness.snapshot().contains("│Animals & Nature"));
+        let picked = harness.press(&[key(KeyCode::Enter)]).unwrap().unwrap();
+        assert_eq!(vec!["crab"], names(&picked));
+    }
+
+    #[test]
+    fn test_mouse() {
+        let mut harness = Harness::new(40, 10);
+        harness.type_text("cra");
+
+        // clicking a suggestion highlights it, clicking below them doesn't
+        assert!(harness.click(5, 5).is_none());
+        assert_eq!(Some(1), harness.app.carousel.position());
+        assert!(harness.snapshot().contains("│> crayon "));
+        harness.click(5, 7);
+        assert_eq!(Some(1), harness.app.carousel.position());
+
+        // the wheel moves through the suggestions
+        harness.send(mouse(MouseEventKind::ScrollUp, 5, 5));
+        assert_eq!(Some(0), harness.app.carousel.position());
+        harness.send(mouse(MouseEventKind::ScrollDown, 5, 5));
+        assert_eq!(Some(1), harness.app.carousel.position());
+
+        // clicking the search box gives it the focus back
+        harness.click(5, 1);
+        assert_eq!(None, harness.app.carousel.position());
+        assert!(matches!(harness.app.mode, InputMode::Searching));
+
+        // clicking two different suggestions isn't a double click
+        assert!(harness.click(5, 4).is_none());
+        assert!(harness.click(5, 5).is_none());
+        let picked = harness.click(5, 5).unwrap().unwrap();
+        assert_eq!(vec!["crayon"], names(&picked));
+    }
+
+    #[test]
+    fn test_grid_mouse() {
+        let mut harness = Harness::new(40, 12);
+        harness.press(&[key(KeyCode::F(2))]);
+
+        // the tabs show the first emoji of each category, beneath them
+        // come the header and the first row of the first category
+        let first = harness.app.carousel.items()[0].emoji.clone();
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains(&format!("│[{}]", first)), "{}", snapshot);
+
+        harness.click(10, 6);
+        assert_eq!(Some(2), harness.app.carousel.position());
+
+        // a tab highlights the first emoji of its category
+        harness.click(6, 4);
+        let position = harness.app.carousel.position();
+        let second = harness.app.carousel.select().unwrap().clone();
+        assert_ne!(harness.app.carousel.items()[0].category, second.category);
+        let snapshot = harness.snapshot();
+        assert!(
+            snapshot.contains(&format!("│ {} [{}]", first, second.emoji)),
+            "{}",
+            snapshot
+        );
+        assert!(snapshot.contains(&format!("│{}", second.category)));
+
+        // clicking the category's header, now at the top, does the same
+        harness.click(1, 0);
+        harness.click(6, 5);
+        assert_eq!(position, harness.app.carousel.position());
+
+        // double clicking an emoji copies it
+        assert!(harness.click(2, 6).is_none());
+        let picked = harness.click(2, 6).unwrap().unwrap();
+        assert_eq!(vec![second.description.as_str()], names(&picked));
+    }
+
+    #[test]
+    fn test_theme() {
+        let config = Config {
+            color: ColorMode::Always,
+            ..Default::default()
+        };
+        let mut harness = Harness::with_config(&config, 40, 10);
+        harness.type_text("cra");
+        assert_eq!(Color::Yellow, harness.cell(1, 1).fg);
+
+        // without colors only the modifiers of the theme are left
+        let config = Config {
+            color: ColorMode::Never,
+            theme: ThemeConfig {
+                name: ThemeName::Solarized,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut harness = Harness::with_config(&config, 40, 10);
+        harness.type_text("cra");
+        harness.press(&[key(KeyCode::Down)]);
+        let highlighted = harness.cell(3, 4);
+        assert_eq!(
+            (Color::Reset, Color::Reset),
+            (highlighted.fg, highlighted.bg)
+        );
+        assert!(highlighted.modifier.contains(Modifier::BOLD));
+
+        // an invalid theme is reported before the picker starts
+        let config = Config {
+            theme: ThemeConfig {
+                borders: Some(String::from("blinking")),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let app = Harness::app(&config);
+        assert!(matches!(
+            app,
+            Err(Error::InvalidTheme {
+                source: ThemeError::UnknownColor(_)
             })
-            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
-            .highlight_symbol("> ");
+        ));
+    }
+
+    #[test]
+    fn test_run_app() {
+        let mut terminal = Terminal::new(TestBackend::new(40, 10)).unwrap();
+        let app = Harness::app(&Config::default()).unwrap();
+
+        let mut script = Script(VecDeque::from([
+            Event::Key(key(KeyCode::Char('s'))),
+            Event::FocusLost,
+            Event::FocusGained,
+            Event::Resize(40, 10),
+            Event::Paste(String::from("nake")),
+            Event::Key(key(KeyCode::Down)),
+            Event::Key(key(KeyCode::Enter)),
+        ]));
+
+        let picked = run_app(
+            &mut terminal,
+            app,
+            &mut script,
+            &mut Pieces(&[]),
+            Duration::ZERO,
+        )
+        .unwrap();
+        assert_eq!(vec!["snake"], names(&picked));
+        assert!(script.0.is_empty());
+    }
+
+    #[test]
+    fn test_render_check() {
+        let mut terminal = Terminal::new(TestBackend::new(50, 10)).unwrap();
+        let app = Harness::app(&Config::default()).unwrap();
+
+        let mut script = Script(VecDeque::from([
+            Event::Paste(String::from("red_haired_")),
+            Event::Key(key(KeyCode::Esc)),
+        ]));
+
+        // the emoji the terminal draws in pieces is shown as code points,
+        // while the others are left alone
+        let canceled = run_app(
+            &mut terminal,
+            app,
+            &mut script,
+            &mut Pieces(&["👩‍🦰"]),
+            Duration::ZERO,
+        );
+        assert!(matches!(canceled, Err(Error::UserCanceledProgram)));
 
-        // We can now render the emoji suggestions
-        f.render_stateful_widget(items, chunks[1], &mut app.items.state);
+        let snapshot = snapshot(&terminal);
+        assert!(
+            snapshot.contains("│red_haired_woman    U+1F469 U+200D U+1F9B0"),
+            "{}",
+            snapshot
+        );
+        assert!(snapshot.contains("│red_haired_man      👨‍🦰"), "{}", snapshot);
     }
 }
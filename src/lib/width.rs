@@ -0,0 +1,99 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+// Marks the end of a name that was cut short to fit its column
+const ELLIPSIS: char = '…';
+
+// `display_width` is the number of terminal columns `text` takes up. Emoji
+// sequences are drawn two columns wide by terminals even when the widths of
+// the characters they are made of add up to something else: ❤️ is a narrow
+// heart followed by a zero-width variation selector, while 👋🏽 is two wide
+// characters
+pub fn display_width(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_width).sum()
+}
+
+fn grapheme_width(grapheme: &str) -> usize {
+    if grapheme.contains('\u{FE0F}') || grapheme.contains('\u{200D}') {
+        2
+    } else {
+        grapheme.width().min(2)
+    }
+}
+
+// `truncate` cuts `text` down to at most `width` columns, ending it with an
+// ellipsis if anything had to be left out. Graphemes are never split
+pub fn truncate(text: &str, width: usize) -> String {
+    if display_width(text) <= width {
+        return text.to_owned();
+    }
+
+    let mut truncated = String::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true) {
+        let grapheme_width = grapheme_width(grapheme);
+        if used + grapheme_width + 1 > width {
+            break;
+        }
+
+        truncated.push_str(grapheme);
+        used += grapheme_width;
+    }
+
+    if width > 0 {
+        truncated.push(ELLIPSIS);
+    }
+
+    truncated
+}
+
+// `pad` truncates `text` to `width` columns and fills whatever is left of
+// them with spaces
+pub fn pad(text: &str, width: usize) -> String {
+    let mut padded = truncate(text, width);
+    let padding = width.saturating_sub(display_width(&padded));
+    padded.push_str(&" ".repeat(padding));
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::width::{display_width, pad, truncate};
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(4, display_width("crab"));
+        assert_eq!(2, display_width("🦀"));
+
+        // narrow characters made into emojis by a variation selector
+        assert_eq!(2, display_width("🖍️"));
+        assert_eq!(2, display_width("❤️"));
+        assert_eq!(2, display_width("#️⃣"));
+
+        // skin tones, flags and joined sequences are drawn as one emoji
+        assert_eq!(2, display_width("👋🏽"));
+        assert_eq!(2, display_width("🇫🇷"));
+        assert_eq!(2, display_width("👨‍👩‍👧"));
+
+        assert_eq!(7, display_width("caf\u{e9} 🦀"));
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!("crab", truncate("crab", 4));
+        assert_eq!("cra…", truncate("crabs", 4));
+        assert_eq!("…", truncate("crabs", 1));
+        assert_eq!("", truncate("crabs", 0));
+
+        // wide characters are left out rather than split
+        assert_eq!("🦀…", truncate("🦀🦀🦀", 4));
+        assert_eq!("…", truncate("🦀🦀🦀", 2));
+    }
+
+    #[test]
+    fn test_pad() {
+        assert_eq!("crab  ", pad("crab", 6));
+        assert_eq!("🖍️  ", pad("🖍️", 4));
+        assert_eq!("thumb…", pad("thumbsup", 6));
+    }
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "cli")]
+
+use std::env;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+// `emojicp` runs the binary with a clean environment, so that the settings
+// of whoever runs the tests don't leak in
+fn emojicp(args: &[&str], vars: &[(&str, &str)]) -> Output {
+    let home: PathBuf = env::temp_dir().join("emojicp-tests-home");
+
+    Command::new(env!("CARGO_BIN_EXE_emojicp"))
+        .args(args)
+        .env_clear()
+        .env("HOME", &home)
+        .env("PATH", env::var_os("PATH").unwrap_or_default())
+        .envs(vars.iter().copied())
+        .output()
+        .expect("emojicp runs")
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn test_success() {
+    let output = emojicp(&["--output", "stdout", "crab", "snake"], &[]);
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!("🦀🐍\n", String::from_utf8_lossy(&output.stdout));
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_not_found() {
+    let output = emojicp(&["--output", "stdout", "crab", "ferris"], &[]);
+    assert_eq!(Some(1), output.status.code());
+    assert!(output.stdout.is_empty());
+
+    // a readable message rather than the Debug representation
+    assert_eq!("emojicp: no emoji is named `ferris`\n", stderr(&output));
+}
+
+#[test]
+fn test_search() {
+    let output = emojicp(&["search", "^crab$"], &[]);
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!("🦀 crab\n", String::from_utf8_lossy(&output.stdout));
+
+    let output = emojicp(&["search", "ferrari"], &[]);
+    assert_eq!(Some(1), output.status.code());
+    assert_eq!("emojicp: no emoji matches `ferrari`\n", stderr(&output));
+
+    let output = emojicp(&["search", "cat:", "crab"], &[]);
+    assert_eq!(Some(2), output.status.code());
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        "emojicp: invalid search query: `cat:` has to be followed by a value\n",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_search_regex() {
+    let output = emojicp(&["search", "--regex", "^(cat|dog)$"], &[]);
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!("🐱 cat\n🐶 dog\n", String::from_utf8_lossy(&output.stdout));
+
+    let output = emojicp(&["search", "--regex", "ferrari"], &[]);
+    assert_eq!(Some(1), output.status.code());
+
+    let output = emojicp(&["search", "--regex", "cat("], &[]);
+    assert_eq!(Some(2), output.status.code());
+    let message = stderr(&output);
+    assert!(
+        message.starts_with("emojicp: invalid regular expression: regex parse error:"),
+        "{}",
+        message
+    );
+    assert!(message.ends_with("error: unclosed group\n"), "{}", message);
+}
+
+#[test]
+fn test_unsupported() {
+    let args = ["--output", "stdout", "--max-version", "12.0"];
+
+    let output = emojicp(&[&args[..], &["crab", "yawning_face"]].concat(), &[]);
+    assert_eq!(Some(0), output.status.code());
+
+    let output = emojicp(
+        &[&args[..], &["crab", "smiling_face_with_tear"]].concat(),
+        &[],
+    );
+    assert_eq!(Some(1), output.status.code());
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        "emojicp: `smiling_face_with_tear` was introduced in Emoji 13.0, newer than 12.0\n",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn test_usage() {
+    let output = emojicp(&["--output", "printer", "crab"], &[]);
+    assert_eq!(Some(2), output.status.code());
+
+    let output = emojicp(&["help", "nonsense"], &[]);
+    assert_eq!(Some(2), output.status.code());
+}
+
+#[test]
+fn test_clipboard() {
+    let output = emojicp(
+        &["--clipboard-backend", "command", "crab"],
+        &[("EMOJICP_CLIPBOARD_COMMAND", "false")],
+    );
+    assert_eq!(Some(3), output.status.code());
+    assert!(stderr(&output).contains("clipboard"));
+}
+
+#[test]
+fn test_config() {
+    let output = emojicp(&["--config", "/nonexistent/emojicp.toml", "crab"], &[]);
+    assert_eq!(Some(4), output.status.code());
+    assert!(stderr(&output).contains("/nonexistent/emojicp.toml"));
+
+    let output = emojicp(&["crab"], &[("EMOJICP_RESULT_COUNT", "lots")]);
+    assert_eq!(Some(4), output.status.code());
+    assert!(stderr(&output).contains("EMOJICP_RESULT_COUNT"));
+}